@@ -604,6 +604,89 @@ export async function getExactSourceLine(
   }
 }
 
+/** Where GitHub looks for CODEOWNERS, in its own lookup order. */
+const CODEOWNERS_PATHS = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/** CODEOWNERS lookups by `owner/repo@ref`; a null entry means the repo has none. */
+const codeOwnersCache = new Map<string, { text: string | null; at: number }>();
+const CODEOWNERS_CACHE_TTL_MS = 10 * 60_000;
+const CODEOWNERS_CACHE_MAX_ENTRIES = 500;
+
+/** One CODEOWNERS candidate: its text, null when missing, undefined when the request failed. */
+async function fetchCodeOwnersCandidate(
+  o: string,
+  r: string,
+  ref: string,
+  p: string,
+  headers: Record<string, string>
+): Promise<string | null | undefined> {
+  const url = new URL(
+    `https://api.github.com/repos/${encodeURIComponent(o)}/${encodeURIComponent(r)}/contents/${encodeRepoContentPath(p)}`
+  );
+  url.searchParams.set("ref", ref);
+  try {
+    const controller = new AbortController();
+    const timeoutId = setTimeout(() => controller.abort(), GITHUB_CONTENTS_FETCH_TIMEOUT_MS);
+    const response = await fetch(url.toString(), { headers, signal: controller.signal });
+    clearTimeout(timeoutId);
+    if (response.status === 404) return null;
+    if (!response.ok) return undefined;
+    const ct = (response.headers.get("content-type") || "").toLowerCase();
+    if (ct.includes("application/json")) return null;
+    return await response.text();
+  } catch (err: any) {
+    if (err?.name === "AbortError") {
+      console.warn("[github] getCodeOwnersFile timeout:", o, r, p);
+    } else {
+      console.warn("[github] getCodeOwnersFile error:", err?.message || err, o, r);
+    }
+    return undefined;
+  }
+}
+
+/**
+ * Raw CODEOWNERS contents at `ref`, from the first location GitHub itself would use.
+ * All locations are fetched at once and the answer is cached per repo and ref for a few minutes;
+ * a lookup where any request failed (rate limit, timeout) is not cached.
+ * Returns null if the repo has none or every request fails. Never throws.
+ */
+export async function getCodeOwnersFile(
+  owner: string,
+  repo: string,
+  ref: string,
+  accessToken?: string | null
+): Promise<string | null> {
+  const token = (accessToken && accessToken.trim()) || process.env.GITHUB_PERSONAL_ACCESS_TOKEN || "";
+  const o = String(owner || "").trim();
+  const r = String(repo || "").trim();
+  const rf = String(ref || "").trim();
+  if (!o || !r || !rf) return null;
+
+  const key = `${o.toLowerCase()}/${r.toLowerCase()}@${rf}`;
+  const cached = codeOwnersCache.get(key);
+  if (cached && Date.now() - cached.at < CODEOWNERS_CACHE_TTL_MS) return cached.text;
+
+  const headers: Record<string, string> = {
+    Accept: "application/vnd.github.v3.raw",
+  };
+  if (token) headers["Authorization"] = `Bearer ${token}`;
+
+  const results = await Promise.all(CODEOWNERS_PATHS.map((p) => fetchCodeOwnersCandidate(o, r, rf, p, headers)));
+  // Earlier locations win, as on GitHub.
+  const hit = results.find((text): text is string => typeof text === "string") ?? null;
+  if (!results.includes(undefined)) cacheCodeOwners(key, hit);
+  return hit;
+}
+
+function cacheCodeOwners(key: string, text: string | null): void {
+  codeOwnersCache.delete(key);
+  if (codeOwnersCache.size >= CODEOWNERS_CACHE_MAX_ENTRIES) {
+    const oldest = codeOwnersCache.keys().next().value;
+    if (oldest !== undefined) codeOwnersCache.delete(oldest);
+  }
+  codeOwnersCache.set(key, { text, at: Date.now() });
+}
+
 /**
  * Default branch name for a repository (e.g. `main`). Used when choosing a ref for contents/blame-aligned reads.
  */
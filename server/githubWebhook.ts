@@ -7,7 +7,7 @@
 import type { Request, Response } from "express";
 import { storage } from "./storage";
 import { databaseStorage } from "./database";
-import { getCommitDetail, type GitHubCommitDetail } from "./github";
+import { getCodeOwnersFile, getCommitDetail, type GitHubCommitDetail } from "./github";
 import { attachCompactDiffContextToPushData } from "./pushDiffEnrichment";
 import { decrypt } from "./encryption";
 import { generateCodeSummary, generateSlackMessage } from "./ai";
@@ -137,6 +137,33 @@ export async function resolveRepoAndIntegration(
 }
 
 // --- Phase 3: Build pushData (and optionally fetch additions/deletions from GitHub API) ---
+/** GitHub token for API reads: the server PAT, else the integration owner's OAuth token. */
+async function resolveGitHubToken(integration: any): Promise<string | null> {
+  const pat = process.env.GITHUB_PERSONAL_ACCESS_TOKEN;
+  if (pat?.trim()) return pat;
+  const user = await databaseStorage.getUserById(integration.userId);
+  const raw = (user as any)?.githubToken;
+  return raw && typeof raw === "string" ? raw : null;
+}
+
+/**
+ * CODEOWNERS on the default branch (the copy GitHub applies), so the risk engine can name owners.
+ * Undefined when unavailable; a failed lookup never fails the push.
+ */
+async function loadCodeOwners(pushData: any, integration: any): Promise<string | undefined> {
+  const repoName = String(pushData.repositoryName || "");
+  const ref = pushData.defaultBranch || pushData.commitSha;
+  if (!repoName.includes("/") || !ref) return undefined;
+  const [owner, repo] = repoName.split("/");
+  try {
+    const token = await resolveGitHubToken(integration);
+    return (await getCodeOwnersFile(owner, repo, ref, token)) ?? undefined;
+  } catch (err: any) {
+    console.warn("[Webhook] CODEOWNERS lookup failed:", err?.message || err);
+    return undefined;
+  }
+}
+
 export async function buildPushData(
   eventType: string,
   branch: string,
@@ -155,12 +182,7 @@ export async function buildPushData(
     const commitSha = commit?.id || commit?.sha;
     if (repoName && commitSha && repoName.includes("/")) {
       const [owner, repo] = repoName.split("/");
-      let token: string | null = process.env.GITHUB_PERSONAL_ACCESS_TOKEN || null;
-      if (!token?.trim()) {
-        const user = await databaseStorage.getUserById(integration.userId);
-        const raw = (user as any)?.githubToken;
-        token = raw && typeof raw === "string" ? raw : null;
-      }
+      const token = await resolveGitHubToken(integration);
       const detail = await getCommitDetail(owner, repo, commitSha, token);
       if (detail) {
         additions = detail.stats.additions;
@@ -178,6 +200,7 @@ export async function buildPushData(
     additions: number;
     deletions: number;
     commitSha: string;
+    defaultBranch?: string;
     _githubCommitDetail?: GitHubCommitDetail;
   } = {
     repositoryName: repository.full_name || repository.name || "unknown",
@@ -187,6 +210,7 @@ export async function buildPushData(
     additions,
     deletions,
     commitSha: commit?.id || commit?.sha || "unknown",
+    defaultBranch: repository.default_branch || undefined,
   };
   if (prefetchedCommit) {
     // Reused by attachCompactDiffContextToPushData to avoid a second GET /commits/{sha}.
@@ -435,6 +459,7 @@ export async function persistPushAndNotifications(
     filesChanged: pushData.filesChanged,
     additions: pushData.additions ?? 0,
     deletions: pushData.deletions ?? 0,
+    codeowners: await loadCodeOwners(pushData, integration),
   });
   if (riskResult.impact_score > 0 || riskResult.risk_flags.length > 0) {
   }
@@ -460,6 +485,8 @@ export async function persistPushAndNotifications(
       change_type_tags: riskResult.change_type_tags,
       hotspot_files: riskResult.hotspot_files,
       explanations: riskResult.explanations,
+      flag_hits: riskResult.flag_hits,
+      owners: riskResult.owners,
       diff_enrichment: pushData.diffContext
         ? {
             used: pushData.diffContext.used,
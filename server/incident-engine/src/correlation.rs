@@ -101,8 +101,8 @@ pub fn rank_suspects(
         .risk_score
         .map(|s| (s as f64 / 100.0).min(1.0))
         .unwrap_or(0.0);
-      if commit.risk_score.is_some() && config.correlation_risk_weight > 0.0 {
-        evidence.push(format!("risk score {}", commit.risk_score.unwrap()));
+      if let Some(s) = commit.risk_score.filter(|_| config.correlation_risk_weight > 0.0) {
+        evidence.push(format!("risk score {}", s));
       }
 
       // Critical-path boost: commit touches configured critical paths.
@@ -0,0 +1,459 @@
+//! Public API surface changes: exported / public function and type
+//! declarations added, removed or re-signed in TypeScript/JavaScript, Rust,
+//! Go and Python diffs.
+//!
+//! Only declarations are compared, so a body-only edit of an exported
+//! function is not an API change (even on a one-line function), while a
+//! signature change is. A parameter list spanning several lines is joined up
+//! to its closing `)` first.
+//! Constants compare only their name and type, not their initializer.
+
+use crate::classify::{classify, FileClass, FileRole};
+use crate::diff::{DiffLine, FileDiff, LineKind};
+use crate::findings::{add_paths, name_list};
+use crate::flags::RiskFlag;
+use crate::types::{ApiChange, ApiChangeKind, ApiKind, ExcludedFile, FlagHit};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// `api_surface` weight when a public symbol was removed or re-signed.
+const BREAKING_WEIGHT: i32 = 15;
+/// `api_surface` weight when public symbols were only added.
+const ADDED_WEIGHT: i32 = 5;
+/// Most lines a multi-line declaration is joined over.
+const MAX_DECL_LINES: usize = 20;
+
+/// Declaration patterns per language: (kind, regex with the name in group 1).
+/// Go and Python patterns are anchored at column 0 (package / module level).
+fn patterns(language: &str) -> &'static [(ApiKind, Regex)] {
+  static TS: OnceLock<Vec<(ApiKind, Regex)>> = OnceLock::new();
+  static RUST: OnceLock<Vec<(ApiKind, Regex)>> = OnceLock::new();
+  static GO: OnceLock<Vec<(ApiKind, Regex)>> = OnceLock::new();
+  static PYTHON: OnceLock<Vec<(ApiKind, Regex)>> = OnceLock::new();
+  let compile = |list: &[(ApiKind, &str)]| {
+    list
+      .iter()
+      .map(|(kind, re)| (*kind, Regex::new(re).expect("valid API pattern")))
+      .collect::<Vec<_>>()
+  };
+  match language {
+    "typescript" | "javascript" => TS.get_or_init(|| {
+      compile(&[
+        (
+          ApiKind::Function,
+          r"^\s*export\s+(?:default\s+)?(?:declare\s+)?(?:async\s+)?function\s*\*?\s*([A-Za-z_$][\w$]*)",
+        ),
+        (
+          ApiKind::Class,
+          r"^\s*export\s+(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?class\s+([A-Za-z_$][\w$]*)",
+        ),
+        (
+          ApiKind::Type,
+          r"^\s*export\s+(?:declare\s+)?(?:interface|type|enum|const\s+enum)\s+([A-Za-z_$][\w$]*)",
+        ),
+        (
+          ApiKind::Const,
+          r"^\s*export\s+(?:declare\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)",
+        ),
+      ])
+    }),
+    "rust" => RUST.get_or_init(|| {
+      compile(&[
+        (
+          ApiKind::Function,
+          r#"^\s*pub\s+(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"[^"]*"\s+)?fn\s+([A-Za-z_]\w*)"#,
+        ),
+        (
+          ApiKind::Type,
+          r"^\s*pub\s+(?:struct|enum|trait|type|union)\s+([A-Za-z_]\w*)",
+        ),
+        (ApiKind::Const, r"^\s*pub\s+(?:const|static)\s+([A-Za-z_]\w*)"),
+      ])
+    }),
+    "go" => GO.get_or_init(|| {
+      compile(&[
+        (
+          ApiKind::Function,
+          r"^func\s+(?:\([^)]*\)\s*)?([A-Z]\w*)\s*(?:\[[^\]]*\])?\s*\(",
+        ),
+        (ApiKind::Type, r"^type\s+([A-Z]\w*)"),
+      ])
+    }),
+    "python" => PYTHON.get_or_init(|| {
+      compile(&[
+        (ApiKind::Function, r"^(?:async\s+)?def\s+([A-Za-z]\w*)\s*\("),
+        (ApiKind::Class, r"^class\s+([A-Za-z]\w*)"),
+      ])
+    }),
+    _ => &[],
+  }
+}
+
+/// One public declaration.
+#[derive(Debug)]
+struct Decl {
+  kind: ApiKind,
+  name: String,
+  /// Declaration with whitespace collapsed, for signature comparison;
+  /// for constants, only the head before the initializer, and for
+  /// functions, the head before the body.
+  signature: String,
+  line: Option<u32>,
+}
+
+fn declaration(language: &str, text: &str) -> Option<Decl> {
+  patterns(language).iter().find_map(|(kind, re)| {
+    let name = re.captures(text)?.get(1)?.as_str().to_string();
+    let head = match kind {
+      ApiKind::Const => &text[..initializer_start(text)],
+      ApiKind::Function => &text[..body_start(language, text)],
+      _ => text,
+    };
+    Some(Decl {
+      kind: *kind,
+      name,
+      signature: head.split_whitespace().collect::<Vec<_>>().join(" "),
+      line: None,
+    })
+  })
+}
+
+/// Byte offset of a constant's `=` (an arrow type's `=>` is part of the
+/// annotation), or the line length when there is no initializer.
+fn initializer_start(text: &str) -> usize {
+  let bytes = text.as_bytes();
+  (0..bytes.len())
+    .find(|&i| bytes[i] == b'=' && !matches!(bytes.get(i + 1), Some(b'>' | b'=')))
+    .unwrap_or(text.len())
+}
+
+/// Byte offset of a function body on its declaration line: the first `{`
+/// outside parentheses and brackets (Python: the `:`), or the line length.
+/// Arrow functions are exported as constants and cut at their `=`.
+fn body_start(language: &str, text: &str) -> usize {
+  let opener = if language == "python" { b':' } else { b'{' };
+  let mut depth = 0i32;
+  for (i, &b) in text.as_bytes().iter().enumerate() {
+    match b {
+      b'(' | b'[' => depth += 1,
+      b')' | b']' => depth -= 1,
+      _ if b == opener && depth == 0 => return i,
+      _ => {}
+    }
+  }
+  text.len()
+}
+
+/// Declarations on one side of a hunk (`Removed`: old file, `Added`: new
+/// file) with a changed line. A declaration whose parentheses are still open
+/// at the end of its line is joined with the lines after it up to the
+/// closing `)`, so editing one parameter line re-signs it.
+fn touched_declarations(language: &str, lines: &[DiffLine], changed: LineKind) -> Vec<Decl> {
+  let side: Vec<&DiffLine> = lines
+    .iter()
+    .filter(|l| l.kind == changed || l.kind == LineKind::Context)
+    .collect();
+  let mut out = Vec::new();
+  let mut i = 0;
+  while i < side.len() {
+    if declaration(language, &side[i].text).is_none() {
+      i += 1;
+      continue;
+    }
+    let mut text = side[i].text.clone();
+    let mut depth = paren_depth(&text);
+    let mut end = i + 1;
+    while depth > 0 && end < side.len() && end - i < MAX_DECL_LINES {
+      text.push(' ');
+      text.push_str(&side[end].text);
+      depth += paren_depth(&side[end].text);
+      end += 1;
+    }
+    if side[i..end].iter().any(|l| l.kind == changed) {
+      if let Some(mut decl) = declaration(language, &text) {
+        decl.line = match changed {
+          LineKind::Removed => side[i].old_line,
+          _ => side[i].new_line,
+        };
+        out.push(decl);
+      }
+    }
+    i = end;
+  }
+  out
+}
+
+/// Open minus closed parentheses on a line.
+fn paren_depth(text: &str) -> i32 {
+  text.chars().fold(0, |depth, c| match c {
+    '(' => depth + 1,
+    ')' => depth - 1,
+    _ => depth,
+  })
+}
+
+/// Public declarations added, removed or changed in source files. Tests,
+/// generated and vendored files are not API.
+pub fn detect(
+  diffs: &[FileDiff],
+  classes: &[FileClass],
+  excluded: &[ExcludedFile],
+) -> Vec<ApiChange> {
+  let mut out = Vec::new();
+  for file in diffs {
+    if file.binary || excluded.iter().any(|e| e.path == file.path) {
+      continue;
+    }
+    let class = classes
+      .iter()
+      .find(|c| c.path == file.path)
+      .cloned()
+      .unwrap_or_else(|| classify(&file.path));
+    let Some(language) = class.language.as_deref() else {
+      continue;
+    };
+    if class.role != FileRole::Source {
+      continue;
+    }
+    let mut removed: Vec<Decl> = Vec::new();
+    let mut added: Vec<Decl> = Vec::new();
+    for hunk in &file.hunks {
+      removed.extend(touched_declarations(
+        language,
+        &hunk.lines,
+        LineKind::Removed,
+      ));
+      added.extend(touched_declarations(language, &hunk.lines, LineKind::Added));
+    }
+    for a in &added {
+      let change = match removed.iter().find(|r| r.name == a.name) {
+        Some(r) if r.signature == a.signature => continue,
+        Some(_) => ApiChangeKind::Changed,
+        None => ApiChangeKind::Added,
+      };
+      out.push(api_change(&file.path, a, change));
+    }
+    for r in removed
+      .iter()
+      .filter(|r| !added.iter().any(|a| a.name == r.name))
+    {
+      out.push(api_change(&file.path, r, ApiChangeKind::Removed));
+    }
+  }
+  out
+}
+
+fn api_change(path: &str, decl: &Decl, change: ApiChangeKind) -> ApiChange {
+  ApiChange {
+    path: path.to_string(),
+    symbol: decl.name.clone(),
+    kind: decl.kind,
+    change,
+    line: decl.line.unwrap_or(0),
+  }
+}
+
+/// `api_surface` hit naming the changed symbols, if any.
+pub fn api_surface_hit(changes: &[ApiChange]) -> Option<FlagHit> {
+  if changes.is_empty() {
+    return None;
+  }
+  let breaking = changes.iter().any(|c| c.change != ApiChangeKind::Added);
+  let mut symbols: Vec<&str> = Vec::new();
+  for c in changes {
+    if !symbols.contains(&c.symbol.as_str()) {
+      symbols.push(&c.symbol);
+    }
+  }
+  let named = name_list(&symbols);
+  let mut hit = if breaking {
+    FlagHit::new(
+      RiskFlag::ApiSurface,
+      "api-signature-changed",
+      BREAKING_WEIGHT,
+      &format!("Public API changed or removed: {}", named),
+    )
+  } else {
+    FlagHit::new(
+      RiskFlag::ApiSurface,
+      "api-added",
+      ADDED_WEIGHT,
+      &format!("Public API added: {}", named),
+    )
+  };
+  add_paths(&mut hit, changes.iter().map(|c| c.path.as_str()));
+  Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify_all;
+  use crate::diff::parse_unified_diff;
+
+  use ApiChangeKind as Kind;
+
+  fn changes(diff: &str) -> Vec<(String, Kind)> {
+    let diffs = parse_unified_diff(diff);
+    let paths: Vec<String> = diffs.iter().map(|d| d.path.clone()).collect();
+    detect(&diffs, &classify_all(&paths), &[])
+      .into_iter()
+      .map(|c| (c.symbol, c.change))
+      .collect()
+  }
+
+  fn pairs(v: &[(&str, Kind)]) -> Vec<(String, Kind)> {
+    v.iter().map(|(a, b)| (a.to_string(), *b)).collect()
+  }
+
+  #[test]
+  fn typescript_exports_changed_added_removed() {
+    let diff = "\
+--- a/src/users.ts
++++ b/src/users.ts
+@@ -1,6 +1,6 @@
+-export async function createUser(name: string) {
++export async function createUser(name: string, role: Role) {
+   const id = nextId();
+-  return save(name);
++  return save(name, role);
+ }
+-export type UserDto = { id: string };
++export interface Role { name: string }
+ function helper() {}
+";
+    assert_eq!(
+      changes(diff),
+      pairs(&[
+        ("createUser", Kind::Changed),
+        ("Role", Kind::Added),
+        ("UserDto", Kind::Removed)
+      ])
+    );
+  }
+
+  #[test]
+  fn private_and_body_only_edits_are_ignored() {
+    let diff = "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+-pub(crate) fn internal(a: u8) {}
++pub(crate) fn internal(a: u16) {}
+ pub fn run(input: &Input) -> Output {
+-  let x = 1;
++  let x = 2;
+--- a/app/views.py
++++ b/app/views.py
+@@ -1,2 +1,2 @@
+-def _private(a):
++def _private(a, b):
+     pass
+";
+    assert!(changes(diff).is_empty());
+  }
+
+  #[test]
+  fn rust_go_and_python_public_symbols() {
+    let diff = "\
+--- a/src/score.rs
++++ b/src/score.rs
+@@ -1 +1 @@
+-pub fn compute(a: u8) -> u8 {
++pub fn compute(a: u8, b: u8) -> u8 {
+--- a/pkg/api/handler.go
++++ b/pkg/api/handler.go
+@@ -1,2 +1,2 @@
+-func (h *Handler) Serve(w http.ResponseWriter) {
++func (h *Handler) Serve(w http.ResponseWriter, r *http.Request) {
+ func helper() {}
+--- a/app/client.py
++++ b/app/client.py
+@@ -0,0 +1 @@
++class Client:
+";
+    assert_eq!(
+      changes(diff),
+      pairs(&[
+        ("compute", Kind::Changed),
+        ("Serve", Kind::Changed),
+        ("Client", Kind::Added)
+      ])
+    );
+  }
+
+  #[test]
+  fn one_line_function_body_edits_are_ignored() {
+    let diff = "\
+--- a/src/math.ts
++++ b/src/math.ts
+@@ -1,2 +1,2 @@
+-export function f(a) { return a; }
++export function f(a) { return a + 1; }
+-export function g(a: { x: number }) { return a.x; }
++export function g(a: { x: string }) { return a.x; }
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-pub fn id(a: u8) -> u8 { a }
++pub fn id(a: u8) -> u8 { a + 0 }
+--- a/app/util.py
++++ b/app/util.py
+@@ -1 +1 @@
+-def double(x: int) -> int: return x * 2
++def double(x: int) -> int: return x + x
+";
+    assert_eq!(changes(diff), pairs(&[("g", Kind::Changed)]));
+  }
+
+  #[test]
+  fn multi_line_signatures_are_joined() {
+    let diff = "\
+--- a/src/users.ts
++++ b/src/users.ts
+@@ -1,8 +1,8 @@
+ export function createUser(
+-  name: string,
++  name: string, role: Role,
+ ): User {
+-  return save(name);
++  return save(name, role);
+ }
+ export function deleteUser(
+   id: string,
+ ) {
+";
+    assert_eq!(changes(diff), pairs(&[("createUser", Kind::Changed)]));
+  }
+
+  #[test]
+  fn const_value_edits_are_not_api_changes() {
+    let diff = "\
+--- a/src/config.ts
++++ b/src/config.ts
+@@ -1,3 +1,3 @@
+-export const LIMIT = 1;
++export const LIMIT = 2;
+-export const onSave: (id: string) => void = noop;
++export const onSave: (id: string) => void = persist;
+-export const TIMEOUT: number = 30;
++export const TIMEOUT: string = \"30s\";
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-pub const MAX: u32 = 10;
++pub const MAX: u32 = 20;
+";
+    assert_eq!(changes(diff), pairs(&[("TIMEOUT", Kind::Changed)]));
+  }
+
+  #[test]
+  fn hit_names_symbols_and_weights_breaking_changes() {
+    let diff = "--- a/src/a.ts\n+++ b/src/a.ts\n@@ -1 +0,0 @@\n-export const LIMIT = 5;\n";
+    let diffs = parse_unified_diff(diff);
+    let found = detect(&diffs, &classify_all(&["src/a.ts".to_string()]), &[]);
+    let hit = api_surface_hit(&found).unwrap();
+    assert_eq!(hit.weight, BREAKING_WEIGHT);
+    assert_eq!(hit.explanation, "Public API changed or removed: LIMIT");
+  }
+}
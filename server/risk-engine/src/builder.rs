@@ -0,0 +1,106 @@
+//! Step-by-step construction of an `Input` for library callers.
+
+use crate::types::{CommitInput, FileChange, FileHistory, Input};
+
+/// Builds an `Input`; everything but the commit message is optional.
+///
+/// ```
+/// use risk_engine::Input;
+///
+/// let input = Input::builder("feat(auth): rotate sessions")
+///   .file("src/auth/session.ts")
+///   .additions(25)
+///   .deletions(5)
+///   .build();
+/// assert_eq!(input.files_changed, vec!["src/auth/session.ts"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct InputBuilder {
+  input: Input,
+}
+
+impl Input {
+  pub fn builder(commit_message: impl Into<String>) -> InputBuilder {
+    InputBuilder::new(commit_message)
+  }
+}
+
+impl InputBuilder {
+  pub fn new(commit_message: impl Into<String>) -> Self {
+    Self {
+      input: Input {
+        commit_message: commit_message.into(),
+        ..Input::default()
+      },
+    }
+  }
+
+  /// Add one changed path.
+  pub fn file(mut self, path: impl Into<String>) -> Self {
+    self.input.files_changed.push(path.into());
+    self
+  }
+
+  /// Add several changed paths.
+  pub fn files<S: Into<String>>(mut self, paths: impl IntoIterator<Item = S>) -> Self {
+    self
+      .input
+      .files_changed
+      .extend(paths.into_iter().map(Into::into));
+    self
+  }
+
+  /// Add one file's status (with `previous_path` for a rename); its path is
+  /// added to the changed paths too.
+  pub fn file_change(mut self, change: FileChange) -> Self {
+    if !self.input.files_changed.contains(&change.path) {
+      self.input.files_changed.push(change.path.clone());
+    }
+    self.input.file_changes.push(change);
+    self
+  }
+
+  pub fn additions(mut self, additions: u32) -> Self {
+    self.input.additions = additions;
+    self
+  }
+
+  pub fn deletions(mut self, deletions: u32) -> Self {
+    self.input.deletions = deletions;
+    self
+  }
+
+  /// Unified diff of the change.
+  pub fn diff_text(mut self, diff: impl Into<String>) -> Self {
+    self.input.diff_text = Some(diff.into());
+    self
+  }
+
+  /// `.gitattributes` contents.
+  pub fn gitattributes(mut self, text: impl Into<String>) -> Self {
+    self.input.gitattributes = Some(text.into());
+    self
+  }
+
+  /// CODEOWNERS contents.
+  pub fn codeowners(mut self, text: impl Into<String>) -> Self {
+    self.input.codeowners = Some(text.into());
+    self
+  }
+
+  /// Add one file's history.
+  pub fn history(mut self, entry: FileHistory) -> Self {
+    self.input.history.push(entry);
+    self
+  }
+
+  /// Add one commit of a multi-commit push.
+  pub fn commit(mut self, commit: CommitInput) -> Self {
+    self.input.commits.push(commit);
+    self
+  }
+
+  pub fn build(self) -> Input {
+    self.input
+  }
+}
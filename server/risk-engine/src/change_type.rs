@@ -1,47 +1,145 @@
 //! Change type tags derived from commit message and file paths.
 
+use crate::classify::FileClass;
+use crate::conventional::ConventionalCommit;
+use crate::flags::ChangeType;
+use crate::revert;
+use crate::rules::RuleSet;
 use std::collections::HashSet;
 
-/// Change type tags from commit message and paths.
-pub fn compute_change_type_tags(commit_message: &str, files: &[String]) -> Vec<String> {
+/// Change type tags from commit message and paths (path tags come from `rules`).
+/// A Conventional Commit header decides the message tag; other messages fall
+/// back to keyword heuristics. `revert` and `hotfix` are added either way.
+pub fn compute_change_type_tags(
+  rules: &RuleSet,
+  conventional: Option<&ConventionalCommit>,
+  commit_message: &str,
+  files: &[FileClass],
+) -> Vec<ChangeType> {
   let mut tags = HashSet::new();
-  let msg = commit_message.to_lowercase();
+  match conventional {
+    Some(c) => {
+      tags.insert(c.tag());
+    }
+    None => tags.extend(heuristic_tags(commit_message)),
+  }
+  if revert::is_revert(commit_message) {
+    tags.insert(ChangeType::Revert);
+  }
+  if revert::is_hotfix(commit_message) {
+    tags.insert(ChangeType::Hotfix);
+  }
+
+  tags.extend(rules.match_tags(files));
+
+  let mut v: Vec<ChangeType> = tags.into_iter().collect();
+  v.sort();
+  v
+}
 
-  if msg.starts_with("feat") || msg.contains("feature") {
-    tags.insert("feature".to_string());
+/// Keyword tags for non-conventional messages, matched on whole words
+/// ("feature-flag" and "attests" are words of their own). The leading word
+/// can set any tag ("Fixes login bug"); later words only add refactor, docs
+/// and tests ("add tests for x"), or bugfix for "bug fix".
+fn heuristic_tags(commit_message: &str) -> HashSet<ChangeType> {
+  let msg = commit_message.to_lowercase();
+  let words: Vec<&str> = msg
+    .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+    .filter(|w| !w.is_empty())
+    .collect();
+  let mut tags: HashSet<ChangeType> = words
+    .first()
+    .and_then(|w| word_tag(w))
+    .into_iter()
+    .collect();
+  for word in words.iter().skip(1) {
+    if let Some(tag @ (ChangeType::Refactor | ChangeType::Docs | ChangeType::Tests)) =
+      word_tag(word)
+    {
+      tags.insert(tag);
+    }
   }
-  if msg.starts_with("fix") || msg.contains("bugfix") || msg.contains("bug fix") {
-    tags.insert("bugfix".to_string());
+  if words.windows(2).any(|w| w == ["bug", "fix"]) {
+    tags.insert(ChangeType::Bugfix);
   }
-  if msg.starts_with("refactor") || msg.contains("refactor") {
-    tags.insert("refactor".to_string());
+  tags
+}
+
+/// Tag named by one word, by stem: "fix", "fixes", "fixed", "fixing" all
+/// mean bugfix.
+fn word_tag(word: &str) -> Option<ChangeType> {
+  let tag = match word {
+    "feat" | "feats" | "feature" | "features" => ChangeType::Feature,
+    "fix" | "fixes" | "fixed" | "fixing" | "bugfix" | "bugfixes" => ChangeType::Bugfix,
+    "refactor" | "refactors" | "refactored" | "refactoring" => ChangeType::Refactor,
+    "doc" | "docs" | "readme" | "documentation" => ChangeType::Docs,
+    "test" | "tests" | "tested" | "testing" => ChangeType::Tests,
+    "chore" | "chores" => ChangeType::Chore,
+    _ => return None,
+  };
+  Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify;
+  use crate::conventional;
+
+  fn tags(message: &str, files: &[&str]) -> Vec<String> {
+    let files: Vec<FileClass> = files.iter().map(|p| classify(p)).collect();
+    let parsed = conventional::parse(message);
+    compute_change_type_tags(&RuleSet::builtin(), parsed.as_ref(), message, &files)
+      .iter()
+      .map(ChangeType::to_string)
+      .collect()
   }
-  if msg.starts_with("docs") || msg.contains("readme") || msg.contains("documentation") {
-    tags.insert("docs".to_string());
+
+  #[test]
+  fn conventional_type_wins_over_keywords() {
+    assert_eq!(tags("perf(db): add tests for index", &[]), vec!["perf"]);
+    assert_eq!(tags("fix: feature toggle crash", &[]), vec!["bugfix"]);
   }
-  if msg.starts_with("test") || msg.contains("test:") || msg.contains("tests") {
-    tags.insert("tests".to_string());
+
+  #[test]
+  fn heuristic_ignores_keywords_inside_other_words() {
+    assert!(tags("latest fixes", &[]).is_empty());
+    assert!(tags("feature-flag cleanup", &[]).is_empty());
+    assert!(tags("update attests parser", &[]).is_empty());
+    assert!(tags("prefactor notes", &[]).is_empty());
   }
-  if msg.starts_with("chore") || msg.contains("chore:") {
-    tags.insert("chore".to_string());
+
+  #[test]
+  fn heuristic_matches_word_stems() {
+    assert_eq!(tags("Fix login redirect", &[]), vec!["bugfix"]);
+    assert_eq!(tags("Fixes login bug", &[]), vec!["bugfix"]);
+    assert_eq!(tags("fixing flaky tests", &[]), vec!["bugfix", "tests"]);
+    assert_eq!(
+      tags("Add tests and refactoring of the parser", &[]),
+      vec!["refactor", "tests"]
+    );
+    assert_eq!(tags("Quick bug fix", &[]), vec!["bugfix"]);
+    assert_eq!(tags("Features: export CSV", &[]), vec!["feature"]);
   }
 
-  for path in files {
-    let p = path.to_lowercase();
-    if p.contains("/test")
-      || p.contains("_test.")
-      || p.contains(".test.")
-      || p.contains("/tests/")
-      || p.contains("/spec")
-    {
-      tags.insert("tests".to_string());
-    }
-    if p.contains("/doc") || p.contains("readme") || p.ends_with(".md") {
-      tags.insert("docs".to_string());
-    }
+  #[test]
+  fn revert_and_hotfix_tags_combine_with_the_type_tag() {
+    assert_eq!(
+      tags("Revert \"feat: x\"\n\nThis reverts commit abc1234.", &[]),
+      vec!["revert"]
+    );
+    assert_eq!(
+      tags("fix: hotfix for checkout crash", &[]),
+      vec!["bugfix", "hotfix"]
+    );
+    assert_eq!(tags("Rollback release 1.4", &[]), vec!["hotfix"]);
   }
 
-  let mut v: Vec<String> = tags.into_iter().collect();
-  v.sort();
-  v
+  #[test]
+  fn path_tags_are_added() {
+    assert_eq!(
+      tags("feat: x", &["src/foo.test.ts", "README.md"]),
+      vec!["docs", "feature", "tests"]
+    );
+  }
 }
@@ -0,0 +1,351 @@
+//! File classification: language, role and ecosystem from the path alone.
+//!
+//! Roles are checked in precedence order (vendored before generated before
+//! lockfile ...), so `vendor/foo/foo_test.go` is vendored, not a test.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What a file is for, independent of its language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileRole {
+  Source,
+  Test,
+  Generated,
+  Vendored,
+  Lockfile,
+  /// Dependency manifest (package.json, Cargo.toml, go.mod, requirements.txt, ...).
+  Manifest,
+  Iac,
+  CiWorkflow,
+  Migration,
+  Docs,
+  Config,
+  Asset,
+  Other,
+}
+
+/// Classification of one changed path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct FileClass {
+  pub path: String,
+  pub language: Option<String>,
+  pub role: FileRole,
+  pub ecosystem: Option<String>,
+}
+
+/// Classify every path (same order as the input).
+pub fn classify_all(files: &[String]) -> Vec<FileClass> {
+  files.iter().map(|p| classify(p)).collect()
+}
+
+/// Classify one path.
+pub fn classify(path: &str) -> FileClass {
+  let lower = path.to_lowercase();
+  let name = lower.rsplit('/').next().unwrap_or(&lower);
+  let ext = extension(name);
+  let language = language(name, ext);
+  let role = role(&lower, name, ext, language);
+  let ecosystem = ecosystem(name, language);
+  FileClass {
+    path: path.to_string(),
+    language: language.map(str::to_string),
+    role,
+    ecosystem: ecosystem.map(str::to_string),
+  }
+}
+
+fn extension(name: &str) -> &str {
+  match name.rsplit_once('.') {
+    Some((stem, ext)) if !stem.is_empty() => ext,
+    _ => "",
+  }
+}
+
+/// True if any directory segment of `path` is one of `dirs`.
+fn has_dir(path: &str, dirs: &[&str]) -> bool {
+  let mut segments: Vec<&str> = path.split('/').collect();
+  segments.pop();
+  segments.iter().any(|s| dirs.contains(s))
+}
+
+fn language(name: &str, ext: &str) -> Option<&'static str> {
+  if name == "dockerfile" || name.starts_with("dockerfile.") || ext == "dockerfile" {
+    return Some("dockerfile");
+  }
+  if name == "makefile" {
+    return Some("make");
+  }
+  let lang = match ext {
+    "rs" => "rust",
+    "ts" | "tsx" | "mts" | "cts" => "typescript",
+    "js" | "jsx" | "mjs" | "cjs" => "javascript",
+    "py" | "pyi" => "python",
+    "go" => "go",
+    "java" => "java",
+    "kt" | "kts" => "kotlin",
+    "rb" => "ruby",
+    "php" => "php",
+    "cs" => "csharp",
+    "c" | "h" => "c",
+    "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+    "swift" => "swift",
+    "scala" => "scala",
+    "sql" => "sql",
+    "sh" | "bash" | "zsh" => "shell",
+    "tf" | "tfvars" | "hcl" => "hcl",
+    "yml" | "yaml" => "yaml",
+    "json" => "json",
+    "toml" => "toml",
+    "md" | "mdx" => "markdown",
+    "html" | "htm" => "html",
+    "css" | "scss" | "sass" | "less" => "css",
+    "proto" => "protobuf",
+    "prisma" => "prisma",
+    "vue" => "vue",
+    "svelte" => "svelte",
+    _ => return None,
+  };
+  Some(lang)
+}
+
+fn is_programming_language(lang: &str) -> bool {
+  !matches!(
+    lang,
+    "yaml" | "json" | "toml" | "markdown" | "html" | "css" | "hcl" | "dockerfile" | "make"
+  )
+}
+
+const LOCKFILES: [&str; 10] = [
+  "package-lock.json",
+  "npm-shrinkwrap.json",
+  "yarn.lock",
+  "pnpm-lock.yaml",
+  "cargo.lock",
+  "go.sum",
+  "poetry.lock",
+  "pipfile.lock",
+  "gemfile.lock",
+  "composer.lock",
+];
+
+const MANIFESTS: [&str; 10] = [
+  "package.json",
+  "cargo.toml",
+  "go.mod",
+  "pyproject.toml",
+  "pipfile",
+  "setup.py",
+  "gemfile",
+  "composer.json",
+  "pom.xml",
+  "build.gradle",
+];
+
+fn is_requirements(name: &str) -> bool {
+  name.starts_with("requirements") && name.ends_with(".txt")
+}
+
+fn role(path: &str, name: &str, ext: &str, language: Option<&str>) -> FileRole {
+  if has_dir(
+    path,
+    &[
+      "vendor",
+      "node_modules",
+      "third_party",
+      "third-party",
+      "bower_components",
+    ],
+  ) {
+    return FileRole::Vendored;
+  }
+  if has_dir(
+    path,
+    &["dist", "__generated__", "generated", ".next", "coverage"],
+  ) || name.ends_with(".min.js")
+    || name.ends_with(".min.css")
+    || name.ends_with(".pb.go")
+    || name.ends_with("_pb2.py")
+    || name.ends_with(".g.dart")
+    || name.contains(".generated.")
+    || ext == "map"
+  {
+    return FileRole::Generated;
+  }
+  if LOCKFILES.contains(&name) {
+    return FileRole::Lockfile;
+  }
+  if MANIFESTS.contains(&name) || is_requirements(name) {
+    return FileRole::Manifest;
+  }
+  if path.starts_with(".github/workflows/")
+    || path.contains("/.github/workflows/")
+    || name == ".gitlab-ci.yml"
+    || name == "jenkinsfile"
+    || name == "azure-pipelines.yml"
+    || has_dir(path, &[".circleci", ".buildkite"])
+  {
+    return FileRole::CiWorkflow;
+  }
+  // Directory names only mark manifests and templates: source code under
+  // `charts/` or `cmd/migrate/` is still source.
+  let manifest_ext = matches!(ext, "yml" | "yaml" | "tpl" | "hcl" | "tf");
+  if language == Some("hcl")
+    || language == Some("dockerfile")
+    || (name.starts_with("docker-compose") && matches!(ext, "yml" | "yaml"))
+    || (name.starts_with("deploy") && ext == "sh")
+    || name == "chart.yaml"
+    || (manifest_ext
+      && has_dir(
+        path,
+        &[
+          "terraform",
+          "helm",
+          "charts",
+          "k8s",
+          "kubernetes",
+          "kustomize",
+        ],
+      ))
+  {
+    return FileRole::Iac;
+  }
+  if ((manifest_ext || ext == "sql") && has_dir(path, &["migrations", "migration", "migrate"]))
+    || ext == "prisma"
+  {
+    return FileRole::Migration;
+  }
+  if has_dir(
+    path,
+    &["test", "tests", "__tests__", "spec", "specs", "e2e"],
+  ) || name.contains(".test.")
+    || name.contains(".spec.")
+    || name.ends_with("_test.go")
+    || name.ends_with("_test.py")
+    || (name.starts_with("test_") && ext == "py")
+    || name.ends_with("_spec.rb")
+  {
+    return FileRole::Test;
+  }
+  if matches!(ext, "md" | "mdx" | "rst" | "adoc" | "txt")
+    || has_dir(path, &["docs", "doc"])
+    || name.starts_with("readme")
+    || name.starts_with("changelog")
+    || name.starts_with("license")
+  {
+    return FileRole::Docs;
+  }
+  if name.starts_with(".env")
+    || matches!(
+      ext,
+      "yml" | "yaml" | "toml" | "ini" | "cfg" | "conf" | "properties"
+    )
+    || (ext == "json" && !has_dir(path, &["fixtures", "testdata"]))
+  {
+    return FileRole::Config;
+  }
+  if matches!(
+    ext,
+    "png" | "jpg" | "jpeg" | "gif" | "svg" | "ico" | "webp" | "woff" | "woff2" | "ttf" | "mp4"
+  ) {
+    return FileRole::Asset;
+  }
+  match language {
+    Some(lang) if is_programming_language(lang) => FileRole::Source,
+    _ => FileRole::Other,
+  }
+}
+
+fn ecosystem(name: &str, language: Option<&str>) -> Option<&'static str> {
+  let eco = match name {
+    "package.json"
+    | "package-lock.json"
+    | "npm-shrinkwrap.json"
+    | "yarn.lock"
+    | "pnpm-lock.yaml" => "npm",
+    "cargo.toml" | "cargo.lock" => "cargo",
+    "go.mod" | "go.sum" => "go",
+    "pyproject.toml" | "pipfile" | "pipfile.lock" | "poetry.lock" | "setup.py" => "pip",
+    "gemfile" | "gemfile.lock" => "bundler",
+    "composer.json" | "composer.lock" => "composer",
+    "pom.xml" => "maven",
+    "build.gradle" => "gradle",
+    _ if is_requirements(name) => "pip",
+    _ => match language? {
+      "typescript" | "javascript" | "vue" | "svelte" => "npm",
+      "rust" => "cargo",
+      "go" => "go",
+      "python" => "pip",
+      "ruby" => "bundler",
+      "php" => "composer",
+      "java" | "kotlin" | "scala" => "jvm",
+      _ => return None,
+    },
+  };
+  Some(eco)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn role_of(path: &str) -> FileRole {
+    classify(path).role
+  }
+
+  #[test]
+  fn config_like_paths_get_different_roles() {
+    let c = classify("src/config.rs");
+    assert_eq!(c.role, FileRole::Source);
+    assert_eq!(c.language.as_deref(), Some("rust"));
+    assert_eq!(c.ecosystem.as_deref(), Some("cargo"));
+    assert_eq!(role_of("config.yaml"), FileRole::Config);
+    assert_eq!(role_of("docs/config.md"), FileRole::Docs);
+  }
+
+  #[test]
+  fn classifies_roles() {
+    assert_eq!(role_of("package-lock.json"), FileRole::Lockfile);
+    assert_eq!(role_of("server/risk-engine/Cargo.toml"), FileRole::Manifest);
+    assert_eq!(role_of(".github/workflows/ci.yml"), FileRole::CiWorkflow);
+    assert_eq!(role_of("Dockerfile.production"), FileRole::Iac);
+    assert_eq!(role_of("infra/terraform/main.tf"), FileRole::Iac);
+    assert_eq!(role_of("deploy-production.sh"), FileRole::Iac);
+    assert_eq!(role_of("migrations/0001_init.sql"), FileRole::Migration);
+    assert_eq!(role_of("src/foo.test.ts"), FileRole::Test);
+    assert_eq!(role_of("pkg/x_test.go"), FileRole::Test);
+    assert_eq!(role_of("dist/app.js"), FileRole::Generated);
+    assert_eq!(role_of("api/v1/user.pb.go"), FileRole::Generated);
+    assert_eq!(role_of("vendor/github.com/x/y_test.go"), FileRole::Vendored);
+    assert_eq!(role_of("attached_assets/logo.png"), FileRole::Asset);
+  }
+
+  #[test]
+  fn infra_and_migration_directories_do_not_claim_source_files() {
+    assert_eq!(role_of("charts/api/values.yaml"), FileRole::Iac);
+    assert_eq!(role_of("k8s/base/deployment.yml"), FileRole::Iac);
+    assert_eq!(role_of("helm/api/templates/svc.tpl"), FileRole::Iac);
+    assert_eq!(
+      role_of("client/src/components/charts/Bar.tsx"),
+      FileRole::Source
+    );
+    assert_eq!(role_of("pkg/kubernetes/client.go"), FileRole::Source);
+    assert_eq!(role_of("db/migrate/001_init.sql"), FileRole::Migration);
+    assert_eq!(role_of("cmd/migrate/main.go"), FileRole::Source);
+  }
+
+  #[test]
+  fn ecosystem_from_manifest_or_language() {
+    assert_eq!(
+      classify("requirements-dev.txt").ecosystem.as_deref(),
+      Some("pip")
+    );
+    assert_eq!(classify("requirements-dev.txt").role, FileRole::Manifest);
+    assert_eq!(
+      classify("web/src/App.tsx").ecosystem.as_deref(),
+      Some("npm")
+    );
+    assert_eq!(classify("notes.txt").ecosystem, None);
+  }
+}
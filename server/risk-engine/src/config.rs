@@ -0,0 +1,99 @@
+//! Engine configuration: which rule pack to evaluate and which scorer to use.
+//! Custom rules: RISK_ENGINE_RULES=/path/to/rules.{toml,json}, or a
+//! `risk-rules.toml` / `risk-rules.json` next to the binary.
+//! Logistic model: RISK_ENGINE_MODEL=/path/to/model.{json,toml}, or a
+//! `risk-model.json` / `risk-model.toml` next to the binary.
+//! Scorer: RISK_ENGINE_SCORER=legacy|weighted|logistic; when unset, logistic
+//! if a model file is found and weighted otherwise.
+//! Input limits: RISK_ENGINE_MAX_* (see `limits`).
+
+use crate::error::EngineError;
+use crate::limits::Limits;
+use crate::rules::RuleSet;
+use crate::scorer::{LegacyScorer, LogisticModel, LogisticScorer, Scorer, WeightedScorer};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// File names looked up next to the binary when RISK_ENGINE_RULES is unset.
+const RULE_FILE_NAMES: [&str; 2] = ["risk-rules.toml", "risk-rules.json"];
+/// File names looked up next to the binary when RISK_ENGINE_MODEL is unset.
+const MODEL_FILE_NAMES: [&str; 2] = ["risk-model.json", "risk-model.toml"];
+
+#[derive(Debug, Clone)]
+pub struct Config {
+  /// Path rules for risk flags and change-type tags.
+  pub rules: RuleSet,
+  /// Impact score model.
+  pub scorer: Arc<dyn Scorer>,
+  /// Input size limits enforced by `try_run`.
+  pub limits: Limits,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      rules: RuleSet::builtin(),
+      scorer: Arc::new(WeightedScorer),
+      limits: Limits::default(),
+    }
+  }
+}
+
+impl Config {
+  /// Build config from the environment; built-in rules when no pack is
+  /// found, the scorer from RISK_ENGINE_SCORER or else from whether a model
+  /// is found.
+  pub fn from_env() -> Result<Self, EngineError> {
+    let rules = match find_file("RISK_ENGINE_RULES", &RULE_FILE_NAMES) {
+      Some(path) => RuleSet::load(&path)?,
+      None => RuleSet::builtin(),
+    };
+    Ok(Config {
+      rules,
+      scorer: scorer_from_env()?,
+      limits: Limits::from_env()?,
+    })
+  }
+}
+
+/// Scorer named by RISK_ENGINE_SCORER. Only `logistic` (or no choice) loads
+/// the model file, and `logistic` without one is an error.
+fn scorer_from_env() -> Result<Arc<dyn Scorer>, EngineError> {
+  const VAR: &str = "RISK_ENGINE_SCORER";
+  let choice = std::env::var(VAR).unwrap_or_default();
+  let model = || find_file("RISK_ENGINE_MODEL", &MODEL_FILE_NAMES);
+  let scorer: Arc<dyn Scorer> = match choice.trim() {
+    "legacy" => Arc::new(LegacyScorer),
+    "weighted" => Arc::new(WeightedScorer),
+    "logistic" => {
+      let path = model().ok_or_else(|| {
+        EngineError::config(VAR, "logistic needs a model file (RISK_ENGINE_MODEL)")
+      })?;
+      Arc::new(LogisticScorer::new(LogisticModel::load(&path)?))
+    }
+    "" => match model() {
+      Some(path) => Arc::new(LogisticScorer::new(LogisticModel::load(&path)?)),
+      None => Arc::new(WeightedScorer),
+    },
+    _ => {
+      return Err(EngineError::config(
+        VAR,
+        "expected legacy, weighted or logistic",
+      ))
+    }
+  };
+  Ok(scorer)
+}
+
+/// Path from `var`, else the first of `names` that exists next to the binary.
+fn find_file(var: &str, names: &[&str]) -> Option<PathBuf> {
+  if let Some(p) = std::env::var_os(var).filter(|p| !p.is_empty()) {
+    return Some(PathBuf::from(p));
+  }
+  let exe = std::env::current_exe().ok()?;
+  let dir = exe.parent()?;
+  names
+    .iter()
+    .map(|name| dir.join(name))
+    .find(|p| p.is_file())
+}
@@ -0,0 +1,120 @@
+//! Conventional Commits parsing (`type(scope)!: description` + footers).
+//! See https://www.conventionalcommits.org/en/v1.0.0/.
+
+use crate::flags::ChangeType;
+
+/// Commit types we recognize; anything else falls back to keyword heuristics.
+const KNOWN_TYPES: [&str; 12] = [
+  "feat", "fix", "refactor", "docs", "test", "tests", "chore", "perf", "ci", "build", "revert",
+  "style",
+];
+
+/// Parsed header and footers of a Conventional Commit message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+  /// Lowercased type, e.g. "feat", "fix", "perf".
+  pub kind: String,
+  pub scope: Option<String>,
+  /// `!` before the colon, or a `BREAKING CHANGE:` / `BREAKING-CHANGE:` footer.
+  pub breaking: bool,
+  pub description: String,
+}
+
+impl ConventionalCommit {
+  /// Change-type tag for this commit's type.
+  pub fn tag(&self) -> ChangeType {
+    match self.kind.as_str() {
+      "feat" => ChangeType::Feature,
+      "fix" => ChangeType::Bugfix,
+      "refactor" | "style" => ChangeType::Refactor,
+      "docs" => ChangeType::Docs,
+      "test" | "tests" => ChangeType::Tests,
+      "perf" => ChangeType::Perf,
+      "ci" => ChangeType::Ci,
+      "build" => ChangeType::Build,
+      "revert" => ChangeType::Revert,
+      _ => ChangeType::Chore,
+    }
+  }
+}
+
+/// Parse a commit message; `None` if the header isn't a Conventional Commit.
+pub fn parse(message: &str) -> Option<ConventionalCommit> {
+  let header = message.lines().next()?.trim();
+  let (prefix, description) = header.split_once(':')?;
+  let description = description.strip_prefix(' ')?.trim();
+  if description.is_empty() {
+    return None;
+  }
+
+  let (prefix, bang) = match prefix.strip_suffix('!') {
+    Some(p) => (p, true),
+    None => (prefix, false),
+  };
+  let (kind, scope) = match prefix.split_once('(') {
+    Some((kind, rest)) => {
+      let scope = rest.strip_suffix(')')?.trim();
+      if scope.is_empty() || scope.contains(['(', ')']) {
+        return None;
+      }
+      (kind, Some(scope.to_string()))
+    }
+    None => (prefix, None),
+  };
+  let kind = kind.to_ascii_lowercase();
+  if !KNOWN_TYPES.contains(&kind.as_str()) {
+    return None;
+  }
+
+  Some(ConventionalCommit {
+    kind,
+    scope,
+    breaking: bang || has_breaking_footer(message),
+    description: description.to_string(),
+  })
+}
+
+/// `BREAKING CHANGE:` / `BREAKING-CHANGE:` footer anywhere after the header.
+pub fn has_breaking_footer(message: &str) -> bool {
+  message
+    .lines()
+    .skip(1)
+    .any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_type_scope_and_bang() {
+    let c = parse("feat(api)!: drop v1 endpoints").unwrap();
+    assert_eq!(c.kind, "feat");
+    assert_eq!(c.scope.as_deref(), Some("api"));
+    assert!(c.breaking);
+    assert_eq!(c.description, "drop v1 endpoints");
+    assert_eq!(c.tag(), ChangeType::Feature);
+  }
+
+  #[test]
+  fn breaking_footer_and_new_types() {
+    let c = parse("perf: cache lookups\n\nBREAKING CHANGE: cache must be warmed").unwrap();
+    assert!(c.breaking);
+    assert_eq!(c.tag(), ChangeType::Perf);
+    assert_eq!(parse("ci: bump runner").unwrap().tag(), ChangeType::Ci);
+    assert_eq!(
+      parse("build(deps): bump serde").unwrap().tag(),
+      ChangeType::Build
+    );
+    assert_eq!(parse("revert: feat: x").unwrap().tag(), ChangeType::Revert);
+  }
+
+  #[test]
+  fn rejects_non_conventional_headers() {
+    assert!(parse("latest fixes").is_none());
+    assert!(parse("feature-flag cleanup").is_none());
+    assert!(parse("Note: something").is_none());
+    assert!(parse("fix:no space").is_none());
+    assert!(parse("feat(): empty scope").is_none());
+  }
+}
@@ -0,0 +1,575 @@
+//! Dependency-change analysis for manifest diffs (package.json, Cargo.toml,
+//! go.mod, requirements*.txt).
+//!
+//! Only the diff is available, not the whole file, so each hunk is read line
+//! by line with a small section tracker (`"dependencies": {`, `[dependencies]`,
+//! `require (`). Removed lines give the old versions and added lines the new
+//! ones; a package on both sides with a different version is a bump.
+
+use crate::classify::{classify, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::flags::RiskFlag;
+use crate::types::{DependencyChange, DependencyChangeKind, FlagHit};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// `deps` weight for a new direct dependency or a major bump.
+const NEW_OR_MAJOR_WEIGHT: i32 = 15;
+/// `deps` weight for minor bumps, downgrades, removals and other edits.
+const MINOR_WEIGHT: i32 = 8;
+/// `deps` weight when only patch versions moved.
+const PATCH_WEIGHT: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Manifest {
+  Npm,
+  Cargo,
+  Go,
+  Pip,
+}
+
+impl Manifest {
+  fn from_path(path: &str) -> Option<Self> {
+    let class = classify(path);
+    if class.role != FileRole::Manifest {
+      return None;
+    }
+    let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    match name.as_str() {
+      "package.json" => Some(Self::Npm),
+      "cargo.toml" => Some(Self::Cargo),
+      "go.mod" => Some(Self::Go),
+      _ if name.starts_with("requirements") => Some(Self::Pip),
+      _ => None,
+    }
+  }
+
+  fn ecosystem(self) -> &'static str {
+    match self {
+      Self::Npm => "npm",
+      Self::Cargo => "cargo",
+      Self::Go => "go",
+      Self::Pip => "pip",
+    }
+  }
+}
+
+/// Where a line sits in the manifest. Hunks often start mid-file, so the
+/// section is `Unknown` until a header shows up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Section {
+  Unknown,
+  Deps,
+  /// Cargo `[dependencies.foo]` table: its `version = ".."` belongs to foo.
+  DepTable(String),
+  Other,
+}
+
+/// One dependency line: package name, version spec (if any), direct or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Dep {
+  name: String,
+  version: Option<String>,
+  direct: bool,
+}
+
+/// Dependency changes in every manifest diff, in diff order then by name.
+pub fn analyze(diffs: &[FileDiff]) -> Vec<DependencyChange> {
+  let mut out = Vec::new();
+  for file in diffs {
+    let Some(manifest) = Manifest::from_path(&file.path) else {
+      continue;
+    };
+    let mut old: BTreeMap<String, Dep> = BTreeMap::new();
+    let mut new: BTreeMap<String, Dep> = BTreeMap::new();
+    for hunk in &file.hunks {
+      let mut old_section = Section::Unknown;
+      let mut new_section = Section::Unknown;
+      for line in &hunk.lines {
+        if line.kind != LineKind::Added {
+          if let Some(dep) = parse_line(manifest, &mut old_section, &line.text) {
+            if line.kind == LineKind::Removed {
+              old.insert(dep.name.clone(), dep);
+            }
+          }
+        }
+        if line.kind != LineKind::Removed {
+          if let Some(dep) = parse_line(manifest, &mut new_section, &line.text) {
+            if line.kind == LineKind::Added {
+              new.insert(dep.name.clone(), dep);
+            }
+          }
+        }
+      }
+    }
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+      let (from, to) = (old.get(name), new.get(name));
+      let change = match (from, to) {
+        (None, Some(_)) => DependencyChangeKind::Added,
+        (Some(_), None) => DependencyChangeKind::Removed,
+        (Some(a), Some(b)) if a.version == b.version => continue,
+        (Some(a), Some(b)) => bump_kind(a.version.as_deref(), b.version.as_deref()),
+        (None, None) => continue,
+      };
+      out.push(DependencyChange {
+        path: file.path.clone(),
+        ecosystem: manifest.ecosystem().to_string(),
+        name: name.clone(),
+        change,
+        from: from.and_then(|d| d.version.clone()),
+        to: to.and_then(|d| d.version.clone()),
+        direct: to.or(from).is_some_and(|d| d.direct),
+      });
+    }
+  }
+  out
+}
+
+/// `deps` hit for the riskiest change, if any. New direct dependencies and
+/// major bumps weigh more than minor bumps, which weigh more than patches.
+pub fn dependency_hit(changes: &[DependencyChange]) -> Option<FlagHit> {
+  let level = |c: &DependencyChange| match c.change {
+    DependencyChangeKind::Added if c.direct => 2,
+    DependencyChangeKind::Major => 2,
+    DependencyChangeKind::Patch => 0,
+    _ => 1,
+  };
+  let top = changes.iter().map(level).max()?;
+  let mut hit = match top {
+    2 => FlagHit::new(
+      RiskFlag::Deps,
+      "dependency-added-or-major",
+      NEW_OR_MAJOR_WEIGHT,
+      "New direct dependency or major version bump",
+    ),
+    1 => FlagHit::new(
+      RiskFlag::Deps,
+      "dependency-minor",
+      MINOR_WEIGHT,
+      "Dependency minor bump, downgrade or removal",
+    ),
+    _ => FlagHit::new(
+      RiskFlag::Deps,
+      "dependency-patch",
+      PATCH_WEIGHT,
+      "Dependency patch bump",
+    ),
+  };
+  for c in changes.iter().filter(|c| level(c) == top) {
+    if !hit.paths.contains(&c.path) {
+      hit.paths.push(c.path.clone());
+    }
+  }
+  Some(hit)
+}
+
+/// Major / minor / patch / downgrade, or `Changed` when either side has no
+/// comparable version (git refs, path deps, range rewrites). For 0.x
+/// versions a minor bump is breaking, so it counts as major.
+fn bump_kind(from: Option<&str>, to: Option<&str>) -> DependencyChangeKind {
+  let (Some(a), Some(b)) = (from.and_then(parse_version), to.and_then(parse_version)) else {
+    return DependencyChangeKind::Changed;
+  };
+  if b < a {
+    DependencyChangeKind::Downgrade
+  } else if a.0 != b.0 || (a.0 == 0 && a.1 != b.1) {
+    DependencyChangeKind::Major
+  } else if a.1 != b.1 {
+    DependencyChangeKind::Minor
+  } else if a.2 != b.2 {
+    DependencyChangeKind::Patch
+  } else {
+    DependencyChangeKind::Changed
+  }
+}
+
+/// First `major[.minor[.patch]]` in a version spec (`^1.2.3`, `v0.4`, `>=2`).
+fn parse_version(spec: &str) -> Option<(u64, u64, u64)> {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  let re =
+    RE.get_or_init(|| Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").expect("valid version pattern"));
+  let caps = re.captures(spec)?;
+  let part = |i: usize| {
+    caps
+      .get(i)
+      .and_then(|m| m.as_str().parse().ok())
+      .unwrap_or(0)
+  };
+  Some((part(1), part(2), part(3)))
+}
+
+fn parse_line(manifest: Manifest, section: &mut Section, line: &str) -> Option<Dep> {
+  match manifest {
+    Manifest::Npm => parse_npm(section, line),
+    Manifest::Cargo => parse_cargo(section, line),
+    Manifest::Go => parse_go(section, line),
+    Manifest::Pip => parse_pip(line),
+  }
+}
+
+fn npm_entry() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r#"^\s*"([^"]+)"\s*:\s*"([^"]*)""#).expect("valid npm pattern"))
+}
+
+fn npm_object() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r#"^\s*"([^"]+)"\s*:\s*\{"#).expect("valid npm pattern"))
+}
+
+fn parse_npm(section: &mut Section, line: &str) -> Option<Dep> {
+  if let Some(caps) = npm_object().captures(line) {
+    *section = match &caps[1] {
+      "dependencies" | "devDependencies" | "peerDependencies" | "optionalDependencies" => {
+        Section::Deps
+      }
+      _ => Section::Other,
+    };
+    return None;
+  }
+  if line.trim_start().starts_with('}') {
+    *section = Section::Other;
+    return None;
+  }
+  let caps = npm_entry().captures(line)?;
+  let (name, version) = (&caps[1], &caps[2]);
+  let is_dep = match section {
+    Section::Deps => true,
+    // Mid-file hunk: git gives package.json no hunk header, so a version
+    // bump in the middle of the list rarely shows its `"dependencies": {`.
+    Section::Unknown => !NPM_NON_DEP_KEYS.contains(&name) && looks_like_npm_version(version),
+    _ => false,
+  };
+  is_dep.then(|| Dep {
+    name: name.to_string(),
+    version: Some(version.to_string()),
+    direct: true,
+  })
+}
+
+/// Top-level and `engines` keys whose values look like version specs.
+const NPM_NON_DEP_KEYS: [&str; 8] = [
+  "name", "version", "node", "npm", "yarn", "pnpm", "bun", "vscode",
+];
+
+fn looks_like_npm_version(v: &str) -> bool {
+  let v = v.trim_start_matches(['^', '~', '>', '<', '=', ' ', 'v']);
+  v.starts_with(|c: char| c.is_ascii_digit())
+    || v.starts_with("workspace:")
+    || v.starts_with("npm:")
+}
+
+/// `[package]` keys that look like `name = "x"` entries.
+const CARGO_PACKAGE_KEYS: [&str; 14] = [
+  "name",
+  "version",
+  "edition",
+  "authors",
+  "description",
+  "license",
+  "rust-version",
+  "readme",
+  "repository",
+  "homepage",
+  "documentation",
+  "resolver",
+  "publish",
+  "members",
+];
+
+fn cargo_entry() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r"^\s*([A-Za-z0-9_-]+)\s*=\s*(.+?)\s*$").expect("valid cargo pattern")
+  })
+}
+
+fn cargo_inline_version() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r#"\bversion\s*=\s*"([^"]*)""#).expect("valid cargo pattern"))
+}
+
+fn parse_cargo(section: &mut Section, line: &str) -> Option<Dep> {
+  let trimmed = line.trim();
+  if trimmed.starts_with('[') {
+    let header = trimmed.trim_matches(['[', ']']);
+    *section = match header.rsplit_once("dependencies.") {
+      Some((_, name)) if !name.is_empty() => Section::DepTable(name.to_string()),
+      _ if header.ends_with("dependencies") => Section::Deps,
+      _ => Section::Other,
+    };
+    return None;
+  }
+  if trimmed.starts_with('#') {
+    return None;
+  }
+  let caps = cargo_entry().captures(line)?;
+  let (key, value) = (&caps[1], &caps[2]);
+  match section {
+    Section::DepTable(name) if key == "version" => Some(Dep {
+      name: name.clone(),
+      version: Some(value.trim_matches('"').to_string()),
+      direct: true,
+    }),
+    Section::Deps => Some(Dep {
+      name: key.to_string(),
+      version: cargo_version(value),
+      direct: true,
+    }),
+    Section::Unknown if !CARGO_PACKAGE_KEYS.contains(&key) => {
+      // `[profile]` keys like `opt-level = "s"` are quoted strings too.
+      let version = cargo_version(value).filter(|v| looks_like_cargo_version(v))?;
+      Some(Dep {
+        name: key.to_string(),
+        version: Some(version),
+        direct: true,
+      })
+    }
+    _ => None,
+  }
+}
+
+/// `"1.2"` or `{ version = "1.2", ... }`; `None` for path/git-only deps.
+fn cargo_version(value: &str) -> Option<String> {
+  if let Some(v) = value.strip_prefix('"') {
+    return v.split('"').next().map(str::to_string);
+  }
+  cargo_inline_version()
+    .captures(value)
+    .map(|c| c[1].to_string())
+}
+
+/// `1.2`, `^0.4`, `>=1, <2`, `=1.0.3` or `*`.
+fn looks_like_cargo_version(v: &str) -> bool {
+  let v = v.trim_start_matches(['^', '~', '>', '<', '=', ' ']);
+  v.starts_with(|c: char| c.is_ascii_digit()) || v == "*"
+}
+
+fn go_require() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r"^\s*(?:require\s+)?([A-Za-z0-9._~/-]+\.[A-Za-z0-9._~/-]+)\s+(v\S+)(.*)$")
+      .expect("valid go.mod pattern")
+  })
+}
+
+fn parse_go(section: &mut Section, line: &str) -> Option<Dep> {
+  let trimmed = line.trim();
+  if trimmed.ends_with('(') {
+    *section = if trimmed.starts_with("require") {
+      Section::Deps
+    } else {
+      Section::Other
+    };
+    return None;
+  }
+  if trimmed == ")" {
+    *section = Section::Other;
+    return None;
+  }
+  let single = trimmed.starts_with("require ");
+  if (!single && *section == Section::Other) || trimmed.contains("=>") {
+    return None;
+  }
+  let caps = go_require().captures(line)?;
+  Some(Dep {
+    name: caps[1].to_string(),
+    version: Some(caps[2].to_string()),
+    direct: !caps[3].contains("// indirect"),
+  })
+}
+
+fn pip_requirement() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(
+      r"^([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*((?:===|==|~=|>=|<=|!=|>|<)[^;#]*)?",
+    )
+    .expect("valid requirements pattern")
+  })
+}
+
+fn parse_pip(line: &str) -> Option<Dep> {
+  let trimmed = line.trim();
+  if trimmed.is_empty() || trimmed.starts_with(['#', '-']) || trimmed.contains("://") {
+    return None;
+  }
+  let caps = pip_requirement().captures(trimmed)?;
+  Some(Dep {
+    // PEP 503 normalization, so `Foo_Bar` and `foo-bar` are one package.
+    name: caps[1].to_lowercase().replace(['_', '.'], "-"),
+    version: caps.get(2).map(|m| m.as_str().trim().to_string()),
+    direct: true,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diff::parse_unified_diff;
+  use DependencyChangeKind as Kind;
+
+  fn changes(diff: &str) -> Vec<(String, Kind)> {
+    analyze(&parse_unified_diff(diff))
+      .into_iter()
+      .map(|c| (c.name, c.change))
+      .collect()
+  }
+
+  fn pairs(v: &[(&str, Kind)]) -> Vec<(String, Kind)> {
+    v.iter().map(|(a, b)| (a.to_string(), *b)).collect()
+  }
+
+  #[test]
+  fn package_json_added_removed_and_bumped() {
+    let diff = "\
+--- a/package.json
++++ b/package.json
+@@ -1,8 +1,8 @@
+ {
+-  \"version\": \"1.0.0\",
++  \"version\": \"1.1.0\",
+   \"dependencies\": {
+-    \"react\": \"^17.0.2\",
++    \"react\": \"^18.2.0\",
+-    \"lodash\": \"^4.17.20\",
++    \"lodash\": \"^4.17.21\",
+-    \"moment\": \"^2.29.0\"
++    \"zod\": \"^3.22.0\"
+   }
+ }
+";
+    assert_eq!(
+      changes(diff),
+      pairs(&[
+        ("lodash", Kind::Patch),
+        ("moment", Kind::Removed),
+        ("react", Kind::Major),
+        ("zod", Kind::Added),
+      ])
+    );
+    let hit = dependency_hit(&analyze(&parse_unified_diff(diff))).unwrap();
+    assert_eq!(hit.weight, NEW_OR_MAJOR_WEIGHT);
+    assert_eq!(hit.paths, vec!["package.json"]);
+  }
+
+  #[test]
+  fn package_json_hunk_without_a_section_header() {
+    let diff = "\
+--- a/package.json
++++ b/package.json
+@@ -2,2 +2,2 @@
+   \"name\": \"pushlog\",
+-  \"version\": \"1.0.0\",
++  \"version\": \"1.1.0\",
+@@ -21,2 +21,2 @@
+-    \"node\": \">=18.0.0\"
++    \"node\": \">=20.0.0\"
+   },
+@@ -40,3 +40,3 @@
+     \"tsx\": \"^4.7.0\",
+-    \"typescript\": \"5.3.3\",
++    \"typescript\": \"5.4.2\",
+     \"vite\": \"^5.0.0\"
+";
+    assert_eq!(changes(diff), pairs(&[("typescript", Kind::Minor)]));
+  }
+
+  #[test]
+  fn cargo_toml_sections_and_zero_major() {
+    let diff = "\
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -5,6 +5,7 @@
+ [dependencies]
+-serde = { version = \"1.0.190\", features = [\"derive\"] }
++serde = { version = \"1.0.193\", features = [\"derive\"] }
+-toml = \"0.7\"
++toml = \"0.8\"
++thiserror = \"2\"
+ 
+ [dev-dependencies.insta]
+-version = \"1.30\"
++version = \"1.34\"
+";
+    assert_eq!(
+      changes(diff),
+      pairs(&[
+        ("insta", Kind::Minor),
+        ("serde", Kind::Patch),
+        ("thiserror", Kind::Added),
+        ("toml", Kind::Major),
+      ])
+    );
+  }
+
+  #[test]
+  fn cargo_toml_profile_hunk_without_a_section_header() {
+    let diff = "\
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -12,2 +12,2 @@
+-anyhow = \"1.0.75\"
++anyhow = \"1.0.79\"
+@@ -30,4 +30,4 @@
+ codegen-units = 1
+-opt-level = \"z\"
++opt-level = \"s\"
+-lto = \"fat\"
++lto = \"thin\"
+";
+    assert_eq!(changes(diff), pairs(&[("anyhow", Kind::Patch)]));
+  }
+
+  #[test]
+  fn go_mod_require_block_and_indirect() {
+    let diff = "\
+--- a/go.mod
++++ b/go.mod
+@@ -3,3 +3,4 @@ go 1.21
+ require (
+-\tgithub.com/gin-gonic/gin v1.9.0
++\tgithub.com/gin-gonic/gin v1.9.1
++\tgolang.org/x/sys v0.15.0 // indirect
+ )
+";
+    let out = analyze(&parse_unified_diff(diff));
+    assert_eq!(out.len(), 2);
+    assert_eq!(out[0].change, Kind::Patch);
+    assert_eq!(out[1].name, "golang.org/x/sys");
+    assert!(!out[1].direct);
+    // An indirect addition is not a "new direct dependency".
+    let hit = dependency_hit(&out[1..]).unwrap();
+    assert_eq!(hit.weight, MINOR_WEIGHT);
+  }
+
+  #[test]
+  fn requirements_txt_pins_and_normalized_names() {
+    let diff = "\
+--- a/requirements.txt
++++ b/requirements.txt
+@@ -1,3 +1,3 @@
+ # runtime
+-Django==4.2.7
++django==5.0.1
+-requests_oauthlib>=1.3
++requests-oauthlib>=1.3
+";
+    assert_eq!(changes(diff), pairs(&[("django", Kind::Major)]));
+  }
+
+  #[test]
+  fn patch_only_changes_weigh_least() {
+    let diff =
+      "--- a/requirements.txt\n+++ b/requirements.txt\n@@ -1 +1 @@\n-flask==3.0.0\n+flask==3.0.1\n";
+    let hit = dependency_hit(&analyze(&parse_unified_diff(diff))).unwrap();
+    assert_eq!(
+      (hit.rule.as_str(), hit.weight),
+      ("dependency-patch", PATCH_WEIGHT)
+    );
+  }
+}
@@ -0,0 +1,359 @@
+//! Unified diff parsing: per-file hunks with added/removed line counts.
+//!
+//! Accepts `git diff` output (with or without `diff --git` headers) and
+//! tolerates truncated or excerpted diffs: unknown lines are skipped.
+
+/// Kind of a line inside a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+  Added,
+  Removed,
+  Context,
+}
+
+/// One line of a hunk, with its line number on the side it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+  pub kind: LineKind,
+  /// Line text without the leading `+` / `-` / ` ` marker.
+  pub text: String,
+  /// Line number in the old file (removed and context lines).
+  pub old_line: Option<u32>,
+  /// Line number in the new file (added and context lines).
+  pub new_line: Option<u32>,
+}
+
+/// One `@@ -a,b +c,d @@` hunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hunk {
+  pub old_start: u32,
+  pub old_lines: u32,
+  pub new_start: u32,
+  pub new_lines: u32,
+  /// Section text after the closing `@@` (usually the enclosing function).
+  pub section: String,
+  pub lines: Vec<DiffLine>,
+}
+
+/// All hunks for one file, plus added/removed line counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDiff {
+  /// New path (old path for deletions), without the `a/` / `b/` prefix.
+  pub path: String,
+  pub hunks: Vec<Hunk>,
+  pub additions: u32,
+  pub deletions: u32,
+  pub binary: bool,
+  /// Path before a rename (`rename from` header).
+  pub old_path: Option<String>,
+}
+
+impl FileDiff {
+  pub fn churn(&self) -> u32 {
+    self.additions + self.deletions
+  }
+
+  /// Added lines with their new-file line numbers.
+  pub fn added_lines(&self) -> impl Iterator<Item = &DiffLine> {
+    self
+      .hunks
+      .iter()
+      .flat_map(|h| h.lines.iter())
+      .filter(|l| l.kind == LineKind::Added)
+  }
+}
+
+/// Parse a unified diff into per-file diffs (in the order they appear).
+pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+  let mut files: Vec<FileDiff> = Vec::new();
+  let mut current: Option<FileDiff> = None;
+  // Remaining old/new lines in the current hunk; header-looking lines inside
+  // a hunk (e.g. a removed line starting with "-- ") are content, not headers.
+  let mut old_left = 0u32;
+  let mut new_left = 0u32;
+  let mut old_no = 0u32;
+  let mut new_no = 0u32;
+
+  for line in text.lines() {
+    let in_hunk = old_left > 0 || new_left > 0;
+
+    if in_hunk {
+      if let Some(file) = current.as_mut() {
+        if let Some(hunk) = file.hunks.last_mut() {
+          let (kind, rest) = match line.as_bytes().first() {
+            Some(b'+') => (LineKind::Added, &line[1..]),
+            Some(b'-') => (LineKind::Removed, &line[1..]),
+            Some(b' ') => (LineKind::Context, &line[1..]),
+            // Some tools strip the trailing space from empty context lines.
+            None => (LineKind::Context, ""),
+            Some(b'\\') => continue,
+            _ => {
+              // Truncated hunk: fall through to header handling.
+              old_left = 0;
+              new_left = 0;
+              (LineKind::Context, "")
+            }
+          };
+          if old_left > 0 || new_left > 0 {
+            let (old_line, new_line) = match kind {
+              LineKind::Added => {
+                new_left = new_left.saturating_sub(1);
+                file.additions += 1;
+                new_no += 1;
+                (None, Some(new_no - 1))
+              }
+              LineKind::Removed => {
+                old_left = old_left.saturating_sub(1);
+                file.deletions += 1;
+                old_no += 1;
+                (Some(old_no - 1), None)
+              }
+              LineKind::Context => {
+                old_left = old_left.saturating_sub(1);
+                new_left = new_left.saturating_sub(1);
+                old_no += 1;
+                new_no += 1;
+                (Some(old_no - 1), Some(new_no - 1))
+              }
+            };
+            hunk.lines.push(DiffLine {
+              kind,
+              text: rest.to_string(),
+              old_line,
+              new_line,
+            });
+            continue;
+          }
+        }
+      }
+    }
+
+    if let Some(rest) = line.strip_prefix("diff --git ") {
+      if let Some(f) = current.take() {
+        files.push(f);
+      }
+      current = Some(FileDiff {
+        path: path_from_git_header(rest),
+        ..FileDiff::default()
+      });
+    } else if let Some(rest) = line.strip_prefix("--- ") {
+      let old = strip_prefix_path(rest);
+      // Plain unified diff without a `diff --git` header starts a new file here.
+      let starts_new = match &current {
+        None => true,
+        Some(f) => !f.hunks.is_empty(),
+      };
+      if starts_new {
+        if let Some(f) = current.take() {
+          files.push(f);
+        }
+        current = Some(FileDiff::default());
+      }
+      if let (Some(f), Some(old)) = (current.as_mut(), old) {
+        if f.path.is_empty() {
+          f.path = old;
+        }
+      }
+    } else if let Some(rest) = line.strip_prefix("+++ ") {
+      if let (Some(f), Some(new)) = (current.as_mut(), strip_prefix_path(rest)) {
+        f.path = new;
+      }
+    } else if let Some(rest) = line.strip_prefix("@@ ") {
+      let Some(hunk) = parse_hunk_header(rest) else {
+        continue;
+      };
+      let f = current.get_or_insert_with(FileDiff::default);
+      old_left = hunk.old_lines;
+      new_left = hunk.new_lines;
+      old_no = hunk.old_start;
+      new_no = hunk.new_start;
+      f.hunks.push(hunk);
+    } else if let Some(rest) = line.strip_prefix("rename from ") {
+      if let Some(f) = current.as_mut() {
+        f.old_path = Some(rest.to_string());
+      }
+    } else if let Some(rest) = line.strip_prefix("rename to ") {
+      if let Some(f) = current.as_mut() {
+        f.path = rest.to_string();
+      }
+    } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+      if let Some(f) = current.as_mut() {
+        f.binary = true;
+      }
+    }
+  }
+
+  if let Some(f) = current.take() {
+    files.push(f);
+  }
+  files.retain(|f| !f.path.is_empty());
+  files
+}
+
+/// `a/path b/path` → `path`. Handles spaces when both sides are equal.
+fn path_from_git_header(rest: &str) -> String {
+  let len = rest.len();
+  if len % 2 == 1 {
+    let half = (len - 1) / 2;
+    if rest.is_char_boundary(half) && rest.is_char_boundary(half + 1) {
+      let (a, b) = (&rest[..half], &rest[half + 1..]);
+      if let (Some(a), Some(b)) = (a.strip_prefix("a/"), b.strip_prefix("b/")) {
+        if a == b {
+          return b.to_string();
+        }
+      }
+    }
+  }
+  match rest.find(" b/") {
+    Some(i) => rest[i + 3..].to_string(),
+    None => String::new(),
+  }
+}
+
+/// Path from a `---` / `+++` line; `None` for `/dev/null`.
+fn strip_prefix_path(rest: &str) -> Option<String> {
+  // Drop a trailing tab + timestamp (plain `diff -u` output).
+  let rest = rest.split('\t').next().unwrap_or(rest).trim_end();
+  if rest == "/dev/null" {
+    return None;
+  }
+  let path = rest
+    .strip_prefix("a/")
+    .or_else(|| rest.strip_prefix("b/"))
+    .unwrap_or(rest);
+  Some(path.to_string())
+}
+
+/// `-a,b +c,d @@ section` → Hunk (counts default to 1 when omitted).
+fn parse_hunk_header(rest: &str) -> Option<Hunk> {
+  let (ranges, section) = rest.split_once("@@")?;
+  let mut parts = ranges.split_whitespace();
+  let (old_start, old_lines) = parse_range(parts.next()?.strip_prefix('-')?)?;
+  let (new_start, new_lines) = parse_range(parts.next()?.strip_prefix('+')?)?;
+  Some(Hunk {
+    old_start,
+    old_lines,
+    new_start,
+    new_lines,
+    section: section.trim().to_string(),
+    lines: Vec::new(),
+  })
+}
+
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+  match s.split_once(',') {
+    Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+    None => Some((s.parse().ok()?, 1)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &str = "\
+diff --git a/src/auth.ts b/src/auth.ts
+index 1111111..2222222 100644
+--- a/src/auth.ts
++++ b/src/auth.ts
+@@ -1,4 +1,5 @@ export function login() {
+ import x from \"x\";
+-const a = 1;
++const a = 2;
++const b = 3;
+
+ export {};
+diff --git a/README.md b/README.md
+new file mode 100644
+--- /dev/null
++++ b/README.md
+@@ -0,0 +1,2 @@
++# Title
++-- not a header
+";
+
+  #[test]
+  fn parses_files_hunks_and_counts() {
+    let files = parse_unified_diff(SAMPLE);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "src/auth.ts");
+    assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+    assert_eq!(files[0].hunks[0].section, "export function login() {");
+    assert_eq!(files[1].path, "README.md");
+    assert_eq!((files[1].additions, files[1].deletions), (2, 0));
+  }
+
+  #[test]
+  fn tracks_line_numbers() {
+    let files = parse_unified_diff(SAMPLE);
+    let lines = &files[0].hunks[0].lines;
+    let added: Vec<u32> = lines
+      .iter()
+      .filter(|l| l.kind == LineKind::Added)
+      .filter_map(|l| l.new_line)
+      .collect();
+    assert_eq!(added, vec![2, 3]);
+    let removed: Vec<u32> = lines
+      .iter()
+      .filter(|l| l.kind == LineKind::Removed)
+      .filter_map(|l| l.old_line)
+      .collect();
+    assert_eq!(removed, vec![2]);
+  }
+
+  #[test]
+  fn deleted_file_keeps_old_path_and_binary_is_marked() {
+    let diff = "\
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+";
+    let files = parse_unified_diff(diff);
+    assert_eq!(files[0].path, "old.txt");
+    assert_eq!(files[0].deletions, 1);
+    assert_eq!(files[1].path, "logo.png");
+    assert!(files[1].binary);
+  }
+
+  #[test]
+  fn rename_headers_set_both_paths() {
+    let diff = "\
+diff --git a/src/old name.ts b/src/new name.ts
+similarity index 100%
+rename from src/old name.ts
+rename to src/new name.ts
+diff --git a/lib/a.rs b/core/a.rs
+similarity index 90%
+rename from lib/a.rs
+rename to core/a.rs
+--- a/lib/a.rs
++++ b/core/a.rs
+@@ -1 +1 @@
+-a
++b
+";
+    let files = parse_unified_diff(diff);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "src/new name.ts");
+    assert_eq!(files[0].old_path.as_deref(), Some("src/old name.ts"));
+    assert!(files[0].hunks.is_empty());
+    assert_eq!(files[1].path, "core/a.rs");
+    assert_eq!(files[1].old_path.as_deref(), Some("lib/a.rs"));
+    assert_eq!(files[1].churn(), 2);
+  }
+
+  #[test]
+  fn tolerates_garbage_and_plain_diffs() {
+    assert!(parse_unified_diff("not a diff at all").is_empty());
+    let plain = "--- a/x.rs\t2024-01-01\n+++ b/x.rs\t2024-01-02\n@@ -1 +1 @@\n-a\n+b\n";
+    let files = parse_unified_diff(plain);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, "x.rs");
+    assert_eq!(files[0].churn(), 2);
+  }
+}
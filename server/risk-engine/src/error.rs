@@ -0,0 +1,45 @@
+//! Structured error types for the risk engine.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EngineError {
+  #[error("rules: {origin}: {reason}")]
+  Rules { origin: String, reason: String },
+  #[error("model: {origin}: {reason}")]
+  Model { origin: String, reason: String },
+  #[error("config: {origin}: {reason}")]
+  Config { origin: String, reason: String },
+  #[error("validation: {field}: {reason}")]
+  Validation { field: String, reason: String },
+}
+
+impl EngineError {
+  pub fn rules(origin: &str, reason: &str) -> Self {
+    Self::Rules {
+      origin: origin.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+
+  pub fn model(origin: &str, reason: &str) -> Self {
+    Self::Model {
+      origin: origin.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+
+  pub fn config(origin: &str, reason: &str) -> Self {
+    Self::Config {
+      origin: origin.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+
+  pub fn validation(field: &str, reason: &str) -> Self {
+    Self::Validation {
+      field: field.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+}
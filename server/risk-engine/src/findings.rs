@@ -0,0 +1,110 @@
+//! Helpers shared by the analyzers that report findings: snippet truncation,
+//! "a, b and N more" name lists, and the severity-weighted flag hit built
+//! from a lint's findings.
+
+use crate::flags::{RiskFlag, Severity};
+use crate::types::{FlagHit, MigrationFinding, WorkflowFinding};
+
+/// Max snippet length written to the output.
+pub const MAX_SNIPPET: usize = 120;
+/// Names listed in an explanation before "and N more".
+pub const MAX_NAMED: usize = 5;
+/// Lint flag weight when any finding is high severity.
+pub const HIGH_WEIGHT: i32 = 25;
+/// Lint flag weight when the worst finding is medium severity.
+pub const MEDIUM_WEIGHT: i32 = 15;
+
+/// `text` cut to `MAX_SNIPPET` chars, with `…` when cut.
+pub fn snippet(text: &str) -> String {
+  if text.chars().count() <= MAX_SNIPPET {
+    return text.to_string();
+  }
+  let cut: String = text.chars().take(MAX_SNIPPET).collect();
+  format!("{}…", cut)
+}
+
+/// `names` joined with ", ", the tail past `MAX_NAMED` as "and N more".
+pub fn name_list(names: &[&str]) -> String {
+  match names.len() {
+    n if n > MAX_NAMED => format!(
+      "{} and {} more",
+      names[..MAX_NAMED].join(", "),
+      n - MAX_NAMED
+    ),
+    _ => names.join(", "),
+  }
+}
+
+/// A linter finding that can raise a flag.
+pub trait Lint {
+  fn path(&self) -> &str;
+  fn rule(&self) -> &str;
+  fn severity(&self) -> Severity;
+  fn message(&self) -> &str;
+}
+
+impl Lint for MigrationFinding {
+  fn path(&self) -> &str {
+    &self.path
+  }
+  fn rule(&self) -> &str {
+    &self.rule
+  }
+  fn severity(&self) -> Severity {
+    self.severity
+  }
+  fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl Lint for WorkflowFinding {
+  fn path(&self) -> &str {
+    &self.path
+  }
+  fn rule(&self) -> &str {
+    &self.rule
+  }
+  fn severity(&self) -> Severity {
+    self.severity
+  }
+  fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+/// `flag` hit from the worst finding (the first high one, else the first):
+/// rule `<prefix>-<rule>`, explanation `<label>: <message>`, every path.
+pub fn worst_finding_hit<F: Lint>(
+  flag: RiskFlag,
+  prefix: &str,
+  label: &str,
+  findings: &[F],
+) -> Option<FlagHit> {
+  let worst = findings
+    .iter()
+    .find(|f| f.severity() == Severity::High)
+    .or_else(|| findings.first())?;
+  let weight = if worst.severity() == Severity::High {
+    HIGH_WEIGHT
+  } else {
+    MEDIUM_WEIGHT
+  };
+  let mut hit = FlagHit::new(
+    flag,
+    &format!("{}-{}", prefix, worst.rule().replace('_', "-")),
+    weight,
+    &format!("{}: {}", label, worst.message()),
+  );
+  add_paths(&mut hit, findings.iter().map(Lint::path));
+  Some(hit)
+}
+
+/// Appends each path not already in `hit.paths`.
+pub fn add_paths<'a>(hit: &mut FlagHit, paths: impl IntoIterator<Item = &'a str>) {
+  for path in paths {
+    if !hit.paths.iter().any(|p| p == path) {
+      hit.paths.push(path.to_string());
+    }
+  }
+}
@@ -0,0 +1,286 @@
+//! Typed risk flags and change-type tags. Each variant serializes to its
+//! snake_case name, so the JSON contract is unchanged; `Other` carries names
+//! defined by custom rule packs. Flags and tags sort by name.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// How bad a flag is on its own, independent of the scorer's weights.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+  /// Lowers risk (a discount).
+  Info,
+  Low,
+  Medium,
+  High,
+  Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFlag {
+  Deps,
+  Migration,
+  Auth,
+  Config,
+  Secrets,
+  Payment,
+  /// Infrastructure as code, containers, deploy scripts.
+  Infra,
+  /// CI/CD pipeline definitions.
+  Ci,
+  /// Cryptography: ciphers, signing, key handling.
+  Crypto,
+  SecretLeak,
+  ApiSurface,
+  IncidentHistory,
+  CrossOwner,
+  Unowned,
+  UntestedChange,
+  /// Discount: matching tests changed with the logic.
+  TestsUpdated,
+  /// A commit scores higher than its push as a whole.
+  RiskyCommit,
+  /// A flag from a custom rule pack.
+  #[serde(untagged)]
+  Other(String),
+}
+
+impl RiskFlag {
+  /// Every built-in flag, in name order.
+  pub const BUILTIN: [RiskFlag; 17] = [
+    RiskFlag::ApiSurface,
+    RiskFlag::Auth,
+    RiskFlag::Ci,
+    RiskFlag::Config,
+    RiskFlag::CrossOwner,
+    RiskFlag::Crypto,
+    RiskFlag::Deps,
+    RiskFlag::IncidentHistory,
+    RiskFlag::Infra,
+    RiskFlag::Migration,
+    RiskFlag::Payment,
+    RiskFlag::RiskyCommit,
+    RiskFlag::SecretLeak,
+    RiskFlag::Secrets,
+    RiskFlag::TestsUpdated,
+    RiskFlag::Unowned,
+    RiskFlag::UntestedChange,
+  ];
+
+  /// Flag name as it appears in `risk_flags` / `flag_hits`.
+  pub fn as_str(&self) -> &str {
+    match self {
+      RiskFlag::Deps => "deps",
+      RiskFlag::Migration => "migration",
+      RiskFlag::Auth => "auth",
+      RiskFlag::Config => "config",
+      RiskFlag::Secrets => "secrets",
+      RiskFlag::Payment => "payment",
+      RiskFlag::Infra => "infra",
+      RiskFlag::Ci => "ci",
+      RiskFlag::Crypto => "crypto",
+      RiskFlag::SecretLeak => "secret_leak",
+      RiskFlag::ApiSurface => "api_surface",
+      RiskFlag::IncidentHistory => "incident_history",
+      RiskFlag::CrossOwner => "cross_owner",
+      RiskFlag::Unowned => "unowned",
+      RiskFlag::UntestedChange => "untested_change",
+      RiskFlag::TestsUpdated => "tests_updated",
+      RiskFlag::RiskyCommit => "risky_commit",
+      RiskFlag::Other(name) => name,
+    }
+  }
+
+  pub fn from_name(name: &str) -> Self {
+    Self::BUILTIN
+      .iter()
+      .find(|f| f.as_str() == name)
+      .cloned()
+      .unwrap_or_else(|| RiskFlag::Other(name.to_string()))
+  }
+
+  pub fn severity(&self) -> Severity {
+    match self {
+      RiskFlag::SecretLeak => Severity::Critical,
+      RiskFlag::Auth
+      | RiskFlag::Secrets
+      | RiskFlag::Payment
+      | RiskFlag::Crypto
+      | RiskFlag::Migration => Severity::High,
+      RiskFlag::Deps
+      | RiskFlag::Config
+      | RiskFlag::Infra
+      | RiskFlag::Ci
+      | RiskFlag::ApiSurface
+      | RiskFlag::IncidentHistory
+      | RiskFlag::RiskyCommit
+      | RiskFlag::Other(_) => Severity::Medium,
+      RiskFlag::CrossOwner | RiskFlag::Unowned | RiskFlag::UntestedChange => Severity::Low,
+      RiskFlag::TestsUpdated => Severity::Info,
+    }
+  }
+
+  /// What the flag means; used when a rule gives no explanation of its own.
+  pub fn explanation(&self) -> &'static str {
+    match self {
+      RiskFlag::Deps => "Dependencies changed",
+      RiskFlag::Migration => "Schema or migration changes",
+      RiskFlag::Auth => "Auth or permission-related files changed",
+      RiskFlag::Config => "Config or environment-related files changed",
+      RiskFlag::Secrets => "Possible secrets or credentials area",
+      RiskFlag::Payment => "Payment or billing-related code changed",
+      RiskFlag::Infra => "Infrastructure or deployment definitions changed",
+      RiskFlag::Ci => "CI/CD pipeline changed",
+      RiskFlag::Crypto => "Cryptography-related code changed",
+      RiskFlag::SecretLeak => "Possible secret committed in the diff",
+      RiskFlag::ApiSurface => "Public API changed",
+      RiskFlag::IncidentHistory => "Files with past incidents changed",
+      RiskFlag::CrossOwner => "Change spans several code owners",
+      RiskFlag::Unowned => "Files without a code owner changed",
+      RiskFlag::UntestedChange => "Logic changed without matching test changes",
+      RiskFlag::TestsUpdated => "Matching tests updated for every changed source file",
+      RiskFlag::RiskyCommit => "A commit in the push scores higher than the push",
+      RiskFlag::Other(_) => "",
+    }
+  }
+}
+
+/// Change-type tags, from the commit message and paths.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeType {
+  Feature,
+  Bugfix,
+  Refactor,
+  Docs,
+  Tests,
+  Chore,
+  Perf,
+  Ci,
+  Build,
+  Revert,
+  /// Hotfix, urgent fix or rollback.
+  Hotfix,
+  /// A tag from a custom rule pack.
+  #[serde(untagged)]
+  Other(String),
+}
+
+impl ChangeType {
+  /// Every built-in tag, in name order.
+  pub const BUILTIN: [ChangeType; 11] = [
+    ChangeType::Bugfix,
+    ChangeType::Build,
+    ChangeType::Chore,
+    ChangeType::Ci,
+    ChangeType::Docs,
+    ChangeType::Feature,
+    ChangeType::Hotfix,
+    ChangeType::Perf,
+    ChangeType::Refactor,
+    ChangeType::Revert,
+    ChangeType::Tests,
+  ];
+
+  pub fn as_str(&self) -> &str {
+    match self {
+      ChangeType::Feature => "feature",
+      ChangeType::Bugfix => "bugfix",
+      ChangeType::Refactor => "refactor",
+      ChangeType::Docs => "docs",
+      ChangeType::Tests => "tests",
+      ChangeType::Chore => "chore",
+      ChangeType::Perf => "perf",
+      ChangeType::Ci => "ci",
+      ChangeType::Build => "build",
+      ChangeType::Revert => "revert",
+      ChangeType::Hotfix => "hotfix",
+      ChangeType::Other(name) => name,
+    }
+  }
+
+  pub fn from_name(name: &str) -> Self {
+    Self::BUILTIN
+      .iter()
+      .find(|t| t.as_str() == name)
+      .cloned()
+      .unwrap_or_else(|| ChangeType::Other(name.to_string()))
+  }
+
+  /// Line added to `explanations` for tags worth calling out.
+  pub fn explanation(&self) -> Option<&'static str> {
+    match self {
+      ChangeType::Tests => Some("Test files changed"),
+      ChangeType::Docs => Some("Documentation changed"),
+      _ => None,
+    }
+  }
+}
+
+macro_rules! by_name {
+  ($ty:ty) => {
+    impl fmt::Display for $ty {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+      }
+    }
+
+    impl PartialOrd for $ty {
+      fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+      }
+    }
+
+    impl Ord for $ty {
+      fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+      }
+    }
+  };
+}
+
+by_name!(RiskFlag);
+by_name!(ChangeType);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn names_round_trip_and_unknown_flags_are_kept() {
+    for flag in RiskFlag::BUILTIN {
+      assert_eq!(RiskFlag::from_name(flag.as_str()), flag);
+      let json = serde_json::to_string(&flag).unwrap();
+      assert_eq!(json, format!("\"{}\"", flag));
+      assert_eq!(serde_json::from_str::<RiskFlag>(&json).unwrap(), flag);
+    }
+    for tag in ChangeType::BUILTIN {
+      assert_eq!(ChangeType::from_name(tag.as_str()), tag);
+    }
+    let custom: RiskFlag = serde_json::from_str(r#""pci_scope""#).unwrap();
+    assert_eq!(custom, RiskFlag::Other("pci_scope".to_string()));
+    assert_eq!(serde_json::to_string(&custom).unwrap(), r#""pci_scope""#);
+  }
+
+  #[test]
+  fn builtin_lists_are_sorted_by_name() {
+    assert!(RiskFlag::BUILTIN.windows(2).all(|w| w[0] < w[1]));
+    assert!(ChangeType::BUILTIN.windows(2).all(|w| w[0] < w[1]));
+  }
+
+  #[test]
+  fn severity_and_explanation_metadata() {
+    assert_eq!(RiskFlag::SecretLeak.severity(), Severity::Critical);
+    assert_eq!(RiskFlag::Crypto.severity(), Severity::High);
+    assert_eq!(RiskFlag::TestsUpdated.severity(), Severity::Info);
+    assert!(RiskFlag::BUILTIN
+      .iter()
+      .all(|f| !f.explanation().is_empty()));
+  }
+}
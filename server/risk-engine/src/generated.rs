@@ -0,0 +1,241 @@
+//! Generated and vendored file detection, so machine-written churn doesn't
+//! drive the impact score.
+//!
+//! Sources, in order: path conventions (classified role), `.gitattributes`
+//! `linguist-generated` / `linguist-vendored`, and "@generated" /
+//! "Code generated ... DO NOT EDIT" headers near the top of the diff.
+
+use crate::classify::{FileClass, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::types::ExcludedFile;
+use globset::{Glob, GlobBuilder, GlobMatcher};
+
+/// Header markers only count in the first lines of a file.
+const HEADER_MAX_LINE: u32 = 5;
+
+/// Mark generated/vendored files (updating their role) and return every file
+/// excluded from impact scoring, with the reason.
+pub fn detect_excluded(
+  classes: &mut [FileClass],
+  diffs: &[FileDiff],
+  gitattributes: Option<&str>,
+) -> Vec<ExcludedFile> {
+  let attrs = gitattributes.map(parse_gitattributes).unwrap_or_default();
+  let mut out = Vec::new();
+  for class in classes.iter_mut() {
+    let reason = match class.role {
+      FileRole::Generated => Some("generated_path"),
+      FileRole::Vendored => Some("vendored_path"),
+      FileRole::Lockfile => Some("lockfile"),
+      _ => None,
+    };
+    let reason = reason.or_else(|| {
+      let (role, reason) = match linguist_attr(&attrs, &class.path)? {
+        Linguist::Generated => (FileRole::Generated, "gitattributes_generated"),
+        Linguist::Vendored => (FileRole::Vendored, "gitattributes_vendored"),
+      };
+      class.role = role;
+      Some(reason)
+    });
+    let reason = reason.or_else(|| {
+      let diff = diffs.iter().find(|d| d.path == class.path)?;
+      if !has_generated_header(diff) {
+        return None;
+      }
+      class.role = FileRole::Generated;
+      Some("generated_header")
+    });
+    if let Some(reason) = reason {
+      out.push(ExcludedFile {
+        path: class.path.clone(),
+        reason: reason.to_string(),
+      });
+    }
+  }
+  out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Linguist {
+  Generated,
+  Vendored,
+}
+
+/// One `.gitattributes` line: pattern plus linguist attributes it sets/unsets.
+#[derive(Debug)]
+struct AttrLine {
+  matcher: GlobMatcher,
+  generated: Option<bool>,
+  vendored: Option<bool>,
+}
+
+fn parse_gitattributes(text: &str) -> Vec<AttrLine> {
+  let mut out = Vec::new();
+  for line in text.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let mut parts = line.split_whitespace();
+    let Some(pattern) = parts.next() else {
+      continue;
+    };
+    let mut generated = None;
+    let mut vendored = None;
+    for attr in parts {
+      if let Some(v) = attr_value(attr, "linguist-generated") {
+        generated = Some(v);
+      }
+      if let Some(v) = attr_value(attr, "linguist-vendored") {
+        vendored = Some(v);
+      }
+    }
+    if generated.is_none() && vendored.is_none() {
+      continue;
+    }
+    if let Some(matcher) = pattern_matcher(pattern) {
+      out.push(AttrLine {
+        matcher,
+        generated,
+        vendored,
+      });
+    }
+  }
+  out
+}
+
+/// `name`, `name=true` → true; `-name`, `!name`, `name=false` → false.
+fn attr_value(attr: &str, name: &str) -> Option<bool> {
+  if attr == name || attr == format!("{}=true", name) {
+    return Some(true);
+  }
+  if attr.strip_prefix(['-', '!']) == Some(name) || attr == format!("{}=false", name) {
+    return Some(false);
+  }
+  None
+}
+
+/// gitattributes pattern → glob. Patterns without a `/` match at any depth;
+/// a trailing `/` means everything under that directory.
+fn pattern_matcher(pattern: &str) -> Option<GlobMatcher> {
+  let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+  let mut glob = pattern.trim_start_matches('/').to_string();
+  if glob.ends_with('/') {
+    glob.push_str("**");
+  }
+  if !anchored {
+    glob = format!("**/{}", glob);
+  }
+  let glob: Glob = GlobBuilder::new(&glob)
+    .literal_separator(true)
+    .build()
+    .ok()?;
+  Some(glob.compile_matcher())
+}
+
+/// Last matching line wins, per attribute (gitattributes semantics).
+fn linguist_attr(attrs: &[AttrLine], path: &str) -> Option<Linguist> {
+  let mut generated = false;
+  let mut vendored = false;
+  for line in attrs.iter().filter(|l| l.matcher.is_match(path)) {
+    generated = line.generated.unwrap_or(generated);
+    vendored = line.vendored.unwrap_or(vendored);
+  }
+  if generated {
+    Some(Linguist::Generated)
+  } else if vendored {
+    Some(Linguist::Vendored)
+  } else {
+    None
+  }
+}
+
+/// "@generated" or Go's "Code generated ... DO NOT EDIT." near the top, added
+/// or unchanged (a regenerated file keeps its header as context).
+fn has_generated_header(diff: &FileDiff) -> bool {
+  diff
+    .hunks
+    .iter()
+    .flat_map(|h| &h.lines)
+    .filter(|l| l.kind != LineKind::Removed)
+    .filter(|l| l.new_line.is_some_and(|n| n <= HEADER_MAX_LINE))
+    .any(|l| {
+      l.text.contains("@generated")
+        || (l.text.contains("Code generated") && l.text.contains("DO NOT EDIT"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify_all;
+  use crate::diff::parse_unified_diff;
+
+  fn paths(p: &[&str]) -> Vec<FileClass> {
+    classify_all(&p.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+  }
+
+  #[test]
+  fn excludes_by_path_convention() {
+    let mut classes = paths(&["dist/app.js", "package-lock.json", "src/app.ts"]);
+    let out = detect_excluded(&mut classes, &[], None);
+    let reasons: Vec<(&str, &str)> = out
+      .iter()
+      .map(|e| (e.path.as_str(), e.reason.as_str()))
+      .collect();
+    assert_eq!(
+      reasons,
+      vec![
+        ("dist/app.js", "generated_path"),
+        ("package-lock.json", "lockfile")
+      ]
+    );
+  }
+
+  #[test]
+  fn gitattributes_marks_and_unmarks_files() {
+    let attrs = "\
+# generated clients
+api/client/** linguist-generated=true
+*.snap linguist-generated
+api/client/handwritten.ts -linguist-generated
+third/ linguist-vendored
+";
+    let mut classes = paths(&[
+      "api/client/gen.ts",
+      "api/client/handwritten.ts",
+      "web/__snaps__/a.snap",
+      "third/lib.c",
+    ]);
+    let out = detect_excluded(&mut classes, &[], Some(attrs));
+    let excluded: Vec<&str> = out.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(
+      excluded,
+      vec!["api/client/gen.ts", "web/__snaps__/a.snap", "third/lib.c"]
+    );
+    assert_eq!(classes[0].role, FileRole::Generated);
+    assert_eq!(classes[3].role, FileRole::Vendored);
+    assert_eq!(out[2].reason, "gitattributes_vendored");
+  }
+
+  #[test]
+  fn generated_header_in_diff() {
+    let diff = parse_unified_diff(
+      "--- /dev/null\n+++ b/src/schema.ts\n@@ -0,0 +1,3 @@\n+// @generated by codegen\n+export type A = 1;\n+export type B = 2;\n",
+    );
+    let mut classes = paths(&["src/schema.ts"]);
+    let out = detect_excluded(&mut classes, &diff, None);
+    assert_eq!(out[0].reason, "generated_header");
+    assert_eq!(classes[0].role, FileRole::Generated);
+  }
+
+  #[test]
+  fn unchanged_generated_header_in_context() {
+    let diff = parse_unified_diff(
+      "--- a/pkg/api/client.go\n+++ b/pkg/api/client.go\n@@ -1,4 +1,4 @@\n // Code generated by protoc-gen-go. DO NOT EDIT.\n \n package api\n-const Version = 1\n+const Version = 2\n",
+    );
+    let mut classes = paths(&["pkg/api/client.go"]);
+    let out = detect_excluded(&mut classes, &diff, None);
+    assert_eq!(out[0].reason, "generated_header");
+  }
+}
@@ -0,0 +1,107 @@
+//! Repository history: per-file change frequency, incidents and authors
+//! (from PushLog's DB) turn churn into the classic code-hotspot model,
+//! hotspot = churn × historical instability.
+
+use crate::flags::RiskFlag;
+use crate::types::{ExcludedFile, FileHistory, FlagHit};
+
+/// `incident_history` weight when a changed file caused an incident before.
+const INCIDENT_WEIGHT: i32 = 10;
+/// `incident_history` weight when a changed file caused this many or more.
+const REPEAT_INCIDENTS: u32 = 3;
+const REPEAT_INCIDENT_WEIGHT: i32 = 20;
+
+/// History entry for `path`, if the snapshot has one.
+pub fn lookup<'a>(history: &'a [FileHistory], path: &str) -> Option<&'a FileHistory> {
+  history.iter().find(|h| h.path == path)
+}
+
+/// Historical instability multiplier, 1.0 for files without history.
+/// Log-scaled so a file changed 100 times doesn't dwarf everything else;
+/// each past incident adds a full point.
+pub fn instability(h: Option<&FileHistory>) -> f64 {
+  let Some(h) = h else {
+    return 1.0;
+  };
+  1.0
+    + (1.0 + h.change_count as f64).ln()
+    + (1.0 + h.author_count as f64).ln() / 2.0
+    + h.incident_count as f64
+}
+
+/// `incident_history` hit for changed files with past incidents, if any.
+pub fn incident_hit(
+  history: &[FileHistory],
+  files: &[String],
+  excluded: &[ExcludedFile],
+) -> Option<FlagHit> {
+  let hits: Vec<&FileHistory> = files
+    .iter()
+    .filter(|f| !excluded.iter().any(|e| &e.path == *f))
+    .filter_map(|f| lookup(history, f))
+    .filter(|h| h.incident_count > 0)
+    .collect();
+  let worst = hits.iter().map(|h| h.incident_count).max()?;
+  let mut hit = if worst >= REPEAT_INCIDENTS {
+    FlagHit::new(
+      RiskFlag::IncidentHistory,
+      "incident-history-repeat",
+      REPEAT_INCIDENT_WEIGHT,
+      "File caused several past incidents",
+    )
+  } else {
+    FlagHit::new(
+      RiskFlag::IncidentHistory,
+      "incident-history",
+      INCIDENT_WEIGHT,
+      "File linked to a past incident",
+    )
+  };
+  hit.paths = hits.iter().map(|h| h.path.clone()).collect();
+  Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(path: &str, changes: u32, incidents: u32, authors: u32) -> FileHistory {
+    FileHistory {
+      path: path.to_string(),
+      change_count: changes,
+      incident_count: incidents,
+      author_count: authors,
+    }
+  }
+
+  #[test]
+  fn instability_grows_with_history() {
+    let calm = entry("a.ts", 2, 0, 1);
+    let busy = entry("b.ts", 40, 0, 6);
+    let burned = entry("c.ts", 2, 2, 1);
+    assert_eq!(instability(None), 1.0);
+    assert!(instability(Some(&busy)) > instability(Some(&calm)));
+    assert!(instability(Some(&burned)) > instability(Some(&calm)) + 1.5);
+  }
+
+  #[test]
+  fn incident_hit_weight_and_paths() {
+    let history = vec![
+      entry("src/billing.ts", 10, 1, 2),
+      entry("src/auth.ts", 30, 4, 5),
+      entry("dist/app.js", 5, 9, 1),
+    ];
+    let files: Vec<String> = ["src/billing.ts", "src/auth.ts", "dist/app.js", "README.md"]
+      .iter()
+      .map(|s| s.to_string())
+      .collect();
+    let excluded = vec![ExcludedFile {
+      path: "dist/app.js".to_string(),
+      reason: "generated_path".to_string(),
+    }];
+    let hit = incident_hit(&history, &files, &excluded).unwrap();
+    assert_eq!(hit.weight, REPEAT_INCIDENT_WEIGHT);
+    assert_eq!(hit.paths, vec!["src/billing.ts", "src/auth.ts"]);
+    assert!(incident_hit(&history, &files[3..], &[]).is_none());
+  }
+}
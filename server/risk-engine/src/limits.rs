@@ -0,0 +1,134 @@
+//! Input size limits, checked before any analysis so an oversized push fails
+//! with a clear error instead of growing memory without bound.
+//!
+//! Override with RISK_ENGINE_MAX_INPUT_BYTES, RISK_ENGINE_MAX_FILES,
+//! RISK_ENGINE_MAX_DIFF_BYTES and RISK_ENGINE_MAX_COMMITS.
+
+use crate::error::EngineError;
+use crate::types::Input;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+  /// Raw JSON per input (the whole of stdin, or one `--stream` line).
+  pub max_input_bytes: usize,
+  /// Paths in `files_changed` and `file_changes` (and in each commit's `files`).
+  pub max_files: usize,
+  /// Bytes of `diff_text` (and of each commit's `diff_text`).
+  pub max_diff_bytes: usize,
+  /// Entries in `commits`.
+  pub max_commits: usize,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self {
+      max_input_bytes: 32 << 20,
+      max_files: 10_000,
+      max_diff_bytes: 16 << 20,
+      max_commits: 1_000,
+    }
+  }
+}
+
+impl Limits {
+  /// Defaults, overridden by any RISK_ENGINE_MAX_* variable that is set.
+  pub fn from_env() -> Result<Self, EngineError> {
+    let mut limits = Self::default();
+    for (var, slot) in [
+      ("RISK_ENGINE_MAX_INPUT_BYTES", &mut limits.max_input_bytes),
+      ("RISK_ENGINE_MAX_FILES", &mut limits.max_files),
+      ("RISK_ENGINE_MAX_DIFF_BYTES", &mut limits.max_diff_bytes),
+      ("RISK_ENGINE_MAX_COMMITS", &mut limits.max_commits),
+    ] {
+      let Ok(raw) = std::env::var(var) else {
+        continue;
+      };
+      *slot = raw
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&n: &usize| n > 0)
+        .ok_or_else(|| EngineError::config(var, "expected a positive integer"))?;
+    }
+    Ok(limits)
+  }
+
+  /// First limit `input` exceeds, as a validation error naming the field.
+  pub fn check(&self, input: &Input) -> Result<(), EngineError> {
+    check_count(
+      "files_changed",
+      input.files_changed.len(),
+      self.max_files,
+      "paths",
+    )?;
+    check_count(
+      "file_changes",
+      input.file_changes.len(),
+      self.max_files,
+      "paths",
+    )?;
+    if let Some(diff) = &input.diff_text {
+      check_count("diff_text", diff.len(), self.max_diff_bytes, "bytes")?;
+    }
+    check_count("commits", input.commits.len(), self.max_commits, "commits")?;
+    for (i, commit) in input.commits.iter().enumerate() {
+      let field = |name: &str| format!("commits[{}].{}", i, name);
+      check_count(&field("files"), commit.files.len(), self.max_files, "paths")?;
+      if let Some(diff) = &commit.diff_text {
+        check_count(
+          &field("diff_text"),
+          diff.len(),
+          self.max_diff_bytes,
+          "bytes",
+        )?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn check_count(field: &str, count: usize, max: usize, unit: &str) -> Result<(), EngineError> {
+  if count > max {
+    return Err(EngineError::validation(
+      field,
+      &format!("{} {} exceeds the limit of {}", count, unit, max),
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::CommitInput;
+
+  #[test]
+  fn check_names_the_field_over_its_limit() {
+    let limits = Limits {
+      max_files: 2,
+      max_diff_bytes: 10,
+      ..Limits::default()
+    };
+    let input = Input {
+      files_changed: vec!["a".to_string(), "b".to_string()],
+      diff_text: Some("0123456789".to_string()),
+      commits: vec![CommitInput {
+        files: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        ..CommitInput::default()
+      }],
+      ..Input::default()
+    };
+    match limits.check(&input) {
+      Err(EngineError::Validation { field, reason }) => {
+        assert_eq!(field, "commits[0].files");
+        assert_eq!(reason, "3 paths exceeds the limit of 2");
+      }
+      other => panic!("expected validation error, got {:?}", other),
+    }
+    let small = Input {
+      files_changed: vec!["a".to_string()],
+      ..Input::default()
+    };
+    assert!(limits.check(&small).is_ok());
+  }
+}
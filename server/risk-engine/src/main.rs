@@ -1,22 +1,241 @@
-//! Binary entrypoint: read one JSON object from stdin, write one to stdout.
+//! Binary entrypoint.
+//!
+//! Default: read one JSON object from stdin, write one to stdout: the Output,
+//! or an ErrorOutput (`{error, message, field?}`) with a non-zero exit code.
+//!
+//! `--stream`: read JSON lines from stdin until EOF and write one line per
+//! input. Each input line is an Input plus an optional `id`; each output line
+//! is either the Output with the same `id`, or an ErrorOutput with that `id`.
+//!
+//! `--schema`: print `{schema_version, input, output}` with the JSON Schema
+//! of the input and output objects, and exit.
+//!
+//! Exit codes: 0 ok (stream mode: also when some lines were rejected),
+//! 1 bad configuration, 2 invalid input, 3 I/O failure, 4 internal panic.
 
-use risk_engine::{run, Input};
-use std::io::{self, Read, Write};
+use risk_engine::types::{ErrorOutput, StreamOutput};
+use risk_engine::{
+  input_schema, output_schema, try_run, Config, EngineError, Input, Output, SCHEMA_VERSION,
+};
+use std::io::{self, BufRead, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+const EXIT_CONFIG: i32 = 1;
+const EXIT_INVALID_INPUT: i32 = 2;
+const EXIT_IO: i32 = 3;
+const EXIT_PANIC: i32 = 4;
+
+/// Why a run stopped, with the exit code for it.
+enum Failure {
+  Config(EngineError),
+  Input(ErrorOutput),
+  Io(io::Error),
+  Panic(String),
+}
+
+impl From<io::Error> for Failure {
+  fn from(e: io::Error) -> Self {
+    Failure::Io(e)
+  }
+}
+
+impl Failure {
+  fn exit_code(&self) -> i32 {
+    match self {
+      Failure::Config(_) => EXIT_CONFIG,
+      Failure::Input(_) => EXIT_INVALID_INPUT,
+      Failure::Io(_) => EXIT_IO,
+      Failure::Panic(_) => EXIT_PANIC,
+    }
+  }
+
+  fn to_output(&self) -> ErrorOutput {
+    match self {
+      Failure::Config(e) => ErrorOutput::new(e.to_string()),
+      Failure::Input(e) => e.clone(),
+      Failure::Io(e) => ErrorOutput::new(format!("io: {}", e)),
+      Failure::Panic(msg) => ErrorOutput::new(format!("internal error: {}", msg)),
+    }
+  }
+}
 
 fn main() {
-  if let Err(e) = run_binary() {
-    let _ = writeln!(io::stderr(), "risk-engine error: {}", e);
-    std::process::exit(1);
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let result = if args.iter().any(|a| a == "--schema") {
+    print_schema()
+  } else if args.iter().any(|a| a == "--stream") {
+    run_stream()
+  } else {
+    run_binary()
+  };
+  if let Err(failure) = result {
+    let err = failure.to_output();
+    let _ = writeln!(io::stderr(), "risk-engine error: {}", err.message);
+    // Best effort: stdout may be what failed.
+    let mut stdout = io::stdout();
+    let _ = serde_json::to_writer(&mut stdout, &err);
+    let _ = stdout.flush();
+    std::process::exit(failure.exit_code());
   }
 }
 
-fn run_binary() -> Result<(), Box<dyn std::error::Error>> {
-  let mut raw = String::new();
-  io::stdin().lock().read_to_string(&mut raw)?;
-  let input: Input = serde_json::from_str(&raw)?;
+fn run_binary() -> Result<(), Failure> {
+  let config = Config::from_env().map_err(Failure::Config)?;
+  let raw = read_limited(io::stdin().lock(), config.limits.max_input_bytes)?;
+  let input: Input = serde_json::from_slice(&raw).map_err(|e| Failure::Input(json_error(&e)))?;
+  let out = score(&input, &config)?;
+  let json = serde_json::to_vec(&out).map_err(io::Error::from)?;
+  io::stdout().write_all(&json)?;
+  Ok(())
+}
 
-  let out = run(&input);
-  let json = serde_json::to_vec(&out)?;
+fn print_schema() -> Result<(), Failure> {
+  let schema = serde_json::json!({
+    "schema_version": SCHEMA_VERSION,
+    "input": input_schema(),
+    "output": output_schema(),
+  });
+  let json = serde_json::to_vec_pretty(&schema).map_err(io::Error::from)?;
   io::stdout().write_all(&json)?;
   Ok(())
 }
+
+fn run_stream() -> Result<(), Failure> {
+  let config = Config::from_env().map_err(Failure::Config)?;
+  let limits = config.limits;
+  let mut stdin = io::stdin().lock();
+  let stdout = io::stdout();
+  let mut out = io::BufWriter::new(stdout.lock());
+  let mut panicked = None;
+  let mut line = Vec::new();
+
+  loop {
+    line.clear();
+    let n = (&mut stdin)
+      .take(limits.max_input_bytes as u64 + 1)
+      .read_until(b'\n', &mut line)?;
+    if n == 0 {
+      break;
+    }
+    if line.len() > limits.max_input_bytes && line.last() != Some(&b'\n') {
+      skip_line(&mut stdin)?;
+      write_line(&mut out, &too_large(limits.max_input_bytes))?;
+      continue;
+    }
+
+    // Skip blank lines.
+    let trimmed = String::from_utf8_lossy(&line);
+    let trimmed = trimmed.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+
+    // Parse as a generic value first so errors can still echo the id.
+    let value: serde_json::Value = match serde_json::from_str(trimmed) {
+      Ok(v) => v,
+      Err(e) => {
+        write_line(&mut out, &json_error(&e))?;
+        continue;
+      }
+    };
+    let id = value.get("id").cloned();
+    let input: Input = match serde_json::from_value(value) {
+      Ok(v) => v,
+      Err(e) => {
+        write_line(&mut out, &json_error(&e).with_id(id))?;
+        continue;
+      }
+    };
+
+    match score(&input, &config) {
+      Ok(output) => write_line(&mut out, &StreamOutput { id, output })?,
+      Err(failure) => {
+        write_line(&mut out, &failure.to_output().with_id(id))?;
+        if let Failure::Panic(_) = failure {
+          panicked = Some(failure);
+        }
+      }
+    }
+  }
+
+  out.flush()?;
+  // Other lines were answered; a panic still means an engine bug.
+  match panicked {
+    Some(failure) => Err(failure),
+    None => Ok(()),
+  }
+}
+
+/// Validate and score, turning a limit violation or a panic into a Failure.
+fn score(input: &Input, config: &Config) -> Result<Output, Failure> {
+  match panic::catch_unwind(AssertUnwindSafe(|| try_run(input, config))) {
+    Ok(Ok(out)) => Ok(out),
+    Ok(Err(EngineError::Validation { field, reason })) => {
+      Err(Failure::Input(ErrorOutput::new(reason).with_field(field)))
+    }
+    Ok(Err(e)) => Err(Failure::Config(e)),
+    Err(payload) => {
+      let msg = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".to_string());
+      Err(Failure::Panic(msg))
+    }
+  }
+}
+
+/// All of `reader`, or an input error past `max` bytes.
+fn read_limited(reader: impl Read, max: usize) -> Result<Vec<u8>, Failure> {
+  let mut raw = Vec::new();
+  reader.take(max as u64 + 1).read_to_end(&mut raw)?;
+  if raw.len() > max {
+    return Err(Failure::Input(too_large(max)));
+  }
+  Ok(raw)
+}
+
+/// Discard the rest of an oversized line.
+fn skip_line(reader: &mut impl BufRead) -> io::Result<()> {
+  loop {
+    let buf = reader.fill_buf()?;
+    if buf.is_empty() {
+      return Ok(());
+    }
+    match buf.iter().position(|&b| b == b'\n') {
+      Some(i) => {
+        reader.consume(i + 1);
+        return Ok(());
+      }
+      None => {
+        let len = buf.len();
+        reader.consume(len);
+      }
+    }
+  }
+}
+
+fn too_large(max: usize) -> ErrorOutput {
+  ErrorOutput::new(format!("input exceeds the limit of {} bytes", max))
+}
+
+/// `json parse: ...`, naming the field when serde reports a missing one.
+fn json_error(e: &serde_json::Error) -> ErrorOutput {
+  let message = e.to_string();
+  let field = message
+    .strip_prefix("missing field `")
+    .and_then(|rest| rest.split('`').next())
+    .map(str::to_string);
+  let err = ErrorOutput::new(format!("json parse: {}", message));
+  match field {
+    Some(field) => err.with_field(field),
+    None => err,
+  }
+}
+
+/// One JSON line, flushed so the caller can match it to its request right away.
+fn write_line<T: serde::Serialize>(out: &mut impl Write, value: &T) -> io::Result<()> {
+  serde_json::to_writer(&mut *out, value)?;
+  writeln!(out)?;
+  out.flush()
+}
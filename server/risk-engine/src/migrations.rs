@@ -0,0 +1,330 @@
+//! Migration safety linter: destructive or table-locking SQL in added lines
+//! of `.sql` files and migration files (Prisma/Drizzle SQL, ...). JS/TS
+//! migrations such as Knex's classify as source and are not linted.
+//!
+//! Added lines are joined into statements (split on `;`, `--` comments
+//! dropped) so multi-line `ALTER TABLE` statements are checked as a whole.
+//! Each finding points at the line where its statement starts.
+
+use crate::classify::{classify, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::findings::{snippet, worst_finding_hit};
+use crate::flags::{RiskFlag, Severity};
+use crate::types::{FlagHit, MigrationFinding};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// One lint: id, severity, message and the statement pattern.
+struct Check {
+  rule: &'static str,
+  severity: Severity,
+  message: &'static str,
+  re: Regex,
+}
+
+fn checks() -> &'static [Check] {
+  static CHECKS: OnceLock<Vec<Check>> = OnceLock::new();
+  CHECKS.get_or_init(|| {
+    [
+      (
+        "drop_table",
+        Severity::High,
+        "DROP TABLE deletes the table and its data",
+        r"(?i)\bDROP\s+TABLE\b",
+      ),
+      (
+        "drop_column",
+        Severity::High,
+        "DROP COLUMN deletes data and breaks code still reading the column",
+        r"(?i)\bDROP\s+COLUMN\b",
+      ),
+      (
+        "alter_column_type",
+        Severity::Medium,
+        "ALTER COLUMN TYPE rewrites the table under an exclusive lock",
+        r"(?i)\bALTER\s+COLUMN\s+\S+\s+(?:SET\s+DATA\s+)?TYPE\b",
+      ),
+      (
+        "set_not_null",
+        Severity::Medium,
+        "SET NOT NULL scans the whole table under an exclusive lock",
+        r"(?i)\bALTER\s+COLUMN\s+\S+\s+SET\s+NOT\s+NULL\b",
+      ),
+    ]
+    .into_iter()
+    .map(|(rule, severity, message, re)| Check {
+      rule,
+      severity,
+      message,
+      re: Regex::new(re).expect("valid migration pattern"),
+    })
+    .collect()
+  })
+}
+
+fn add_column() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r"(?i)\bADD\s+(\w+)").expect("valid migration pattern"))
+}
+
+/// `ADD COLUMN x ...` or Postgres' bare `ADD x ...`, but not `ADD CONSTRAINT`.
+fn adds_column(stmt: &str) -> bool {
+  add_column().captures_iter(stmt).any(|c| {
+    !matches!(
+      c[1].to_lowercase().as_str(),
+      "constraint" | "primary" | "foreign" | "unique" | "check" | "index" | "key"
+    )
+  })
+}
+
+fn not_null() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r"(?i)\bNOT\s+NULL\b").expect("valid migration pattern"))
+}
+
+fn default_clause() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r"(?i)\bDEFAULT\b").expect("valid migration pattern"))
+}
+
+fn create_index() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(
+      r#"(?i)\bCREATE\s+(?:UNIQUE\s+)?INDEX\s+(CONCURRENTLY\b)?.*?\bON\s+(?:ONLY\s+)?([\w."]+)"#,
+    )
+    .expect("valid migration pattern")
+  })
+}
+
+fn create_table() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r#"(?i)\bCREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?([\w."]+)"#)
+      .expect("valid migration pattern")
+  })
+}
+
+/// An added statement and the new-file line it starts on.
+struct Statement {
+  line: u32,
+  text: String,
+}
+
+/// Files worth linting: SQL anywhere, plus anything classified as a migration.
+fn is_migration_file(path: &str) -> bool {
+  let class = classify(path);
+  class.language.as_deref() == Some("sql") || class.role == FileRole::Migration
+}
+
+/// Lint added statements of every SQL / migration file in the diff.
+pub fn lint(diffs: &[FileDiff]) -> Vec<MigrationFinding> {
+  let mut out = Vec::new();
+  for file in diffs {
+    if file.binary || !is_migration_file(&file.path) {
+      continue;
+    }
+    let statements = added_statements(file);
+    // Indexes on tables created in the same migration can't block anyone.
+    let created: Vec<String> = statements
+      .iter()
+      .filter_map(|s| create_table().captures(&s.text))
+      .map(|c| table_name(&c[1]))
+      .collect();
+    for stmt in &statements {
+      let mut push = |rule: &str, severity: Severity, message: &str| {
+        out.push(MigrationFinding {
+          path: file.path.clone(),
+          line: stmt.line,
+          rule: rule.to_string(),
+          severity,
+          message: message.to_string(),
+          snippet: snippet(&stmt.text),
+        })
+      };
+      for check in checks() {
+        if check.re.is_match(&stmt.text) {
+          push(check.rule, check.severity, check.message);
+        }
+      }
+      if adds_column(&stmt.text)
+        && not_null().is_match(&stmt.text)
+        && !default_clause().is_match(&stmt.text)
+        && !create_table().is_match(&stmt.text)
+      {
+        push(
+          "not_null_without_default",
+          Severity::High,
+          "NOT NULL column added without a DEFAULT fails on non-empty tables",
+        );
+      }
+      if let Some(caps) = create_index().captures(&stmt.text) {
+        if caps.get(1).is_none() && !created.contains(&table_name(&caps[2])) {
+          push(
+            "create_index_without_concurrently",
+            Severity::Medium,
+            "CREATE INDEX without CONCURRENTLY blocks writes while it builds",
+          );
+        }
+      }
+    }
+  }
+  out
+}
+
+/// `migration` hit from the worst finding (medium or high), if any.
+pub fn migration_hit(findings: &[MigrationFinding]) -> Option<FlagHit> {
+  worst_finding_hit(RiskFlag::Migration, "sql", "Unsafe migration", findings)
+}
+
+/// Consecutive added lines joined into `;`-terminated statements.
+fn added_statements(file: &FileDiff) -> Vec<Statement> {
+  let mut out = Vec::new();
+  for hunk in &file.hunks {
+    let mut current: Option<Statement> = None;
+    for line in &hunk.lines {
+      if line.kind != LineKind::Added {
+        // A removed/context line ends any statement being collected.
+        out.extend(current.take());
+        continue;
+      }
+      let code = strip_comment(&line.text);
+      for (i, part) in code.split(';').enumerate() {
+        if i > 0 {
+          out.extend(current.take());
+        }
+        let part = part.trim();
+        if part.is_empty() {
+          continue;
+        }
+        let stmt = current.get_or_insert_with(|| Statement {
+          line: line.new_line.unwrap_or(0),
+          text: String::new(),
+        });
+        if !stmt.text.is_empty() {
+          stmt.text.push(' ');
+        }
+        stmt.text.push_str(part);
+      }
+    }
+    out.extend(current.take());
+  }
+  out
+}
+
+/// `line` up to a `--` comment; a `--` inside a single-quoted literal is
+/// part of the value.
+fn strip_comment(line: &str) -> &str {
+  let mut quoted = false;
+  let mut prev = None;
+  for (i, c) in line.char_indices() {
+    match c {
+      '\'' => quoted = !quoted,
+      '-' if !quoted && prev == Some('-') => return &line[..i - 1],
+      _ => {}
+    }
+    prev = Some(c);
+  }
+  line
+}
+
+/// Lowercased table name without quotes or schema.
+fn table_name(raw: &str) -> String {
+  let name = raw.rsplit('.').next().unwrap_or(raw);
+  name.trim_matches('"').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diff::parse_unified_diff;
+
+  fn rules(diff: &str) -> Vec<(String, u32)> {
+    lint(&parse_unified_diff(diff))
+      .into_iter()
+      .map(|f| (f.rule, f.line))
+      .collect()
+  }
+
+  #[test]
+  fn flags_destructive_and_locking_statements() {
+    let diff = "\
+--- /dev/null
++++ b/migrations/0007_cleanup.sql
+@@ -0,0 +1,7 @@
++DROP TABLE legacy_sessions;
++ALTER TABLE users DROP COLUMN nickname;
++ALTER TABLE users
++  ALTER COLUMN email TYPE citext;
++ALTER TABLE orders ADD COLUMN region text NOT NULL;
++CREATE INDEX idx_orders_region ON orders (region);
++CREATE INDEX CONCURRENTLY idx_users_email ON users (email); -- fine
+";
+    assert_eq!(
+      rules(diff),
+      vec![
+        ("drop_table".to_string(), 1),
+        ("drop_column".to_string(), 2),
+        ("alter_column_type".to_string(), 3),
+        ("not_null_without_default".to_string(), 5),
+        ("create_index_without_concurrently".to_string(), 6),
+      ]
+    );
+  }
+
+  #[test]
+  fn safe_patterns_are_not_flagged() {
+    let diff = "\
+--- /dev/null
++++ b/prisma/migrations/20240101_init/migration.sql
+@@ -0,0 +1,7 @@
++CREATE TABLE \"Invoice\" (
++  id serial PRIMARY KEY,
++  total integer NOT NULL
++);
++CREATE INDEX \"Invoice_total_idx\" ON \"Invoice\" (total);
++ALTER TABLE users ADD COLUMN active boolean NOT NULL DEFAULT true;
++ALTER TABLE users ADD COLUMN x text NOT NULL DEFAULT '--'; -- placeholder
+";
+    assert!(rules(diff).is_empty());
+  }
+
+  #[test]
+  fn dashes_inside_string_literals_are_not_comments() {
+    let diff = "\
+--- /dev/null
++++ b/migrations/0008_notes.sql
+@@ -0,0 +1,2 @@
++ALTER TABLE orders ADD COLUMN note text DEFAULT '--';
++ALTER TABLE users ADD COLUMN region text NOT NULL; -- backfilled later
+";
+    assert_eq!(
+      rules(diff),
+      vec![("not_null_without_default".to_string(), 2)]
+    );
+  }
+
+  #[test]
+  fn only_added_lines_in_sql_or_migration_files() {
+    let diff = "\
+--- a/db/schema.sql
++++ b/db/schema.sql
+@@ -1,2 +1,1 @@
+-DROP TABLE old;
+ SELECT 1;
+--- a/src/cleanup.ts
++++ b/src/cleanup.ts
+@@ -0,0 +1 @@
++// DROP TABLE is mentioned in a comment here
+";
+    assert!(rules(diff).is_empty());
+  }
+
+  #[test]
+  fn hit_weight_follows_worst_severity() {
+    let diff = "--- a/m.sql\n+++ b/m.sql\n@@ -0,0 +1 @@\n+CREATE INDEX i ON t (c);\n";
+    let hit = migration_hit(&lint(&parse_unified_diff(diff))).unwrap();
+    assert_eq!(hit.weight, crate::findings::MEDIUM_WEIGHT);
+    assert_eq!(hit.rule, "sql-create-index-without-concurrently");
+  }
+}
@@ -0,0 +1,256 @@
+//! CODEOWNERS: owning teams per changed path, plus flags for changes that
+//! cross ownership boundaries or touch unowned paths.
+//!
+//! Follows GitHub's rules: the last matching line wins, a line with no owners
+//! un-owns its paths, patterns without a `/` (other than a trailing one)
+//! match at any depth, and a pattern naming a directory covers everything
+//! below it.
+
+use crate::flags::RiskFlag;
+use crate::types::{ExcludedFile, FileOwners, FlagHit};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// `cross_owner` weight when a change spans two owner groups.
+const CROSS_OWNER_WEIGHT: i32 = 5;
+/// `cross_owner` weight when it spans three or more.
+const MANY_OWNERS_WEIGHT: i32 = 10;
+/// `unowned` weight.
+const UNOWNED_WEIGHT: i32 = 5;
+
+/// One CODEOWNERS line.
+#[derive(Debug)]
+struct OwnerRule {
+  matcher: GlobSet,
+  owners: Vec<String>,
+}
+
+/// Parsed CODEOWNERS file.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+  rules: Vec<OwnerRule>,
+}
+
+impl CodeOwners {
+  /// Parse CODEOWNERS contents. Lines with unusable patterns are skipped,
+  /// as GitHub does.
+  pub fn parse(text: &str) -> Self {
+    let rules = text
+      .lines()
+      .filter_map(|line| {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut parts = line.split_whitespace();
+        let pattern = parts.next()?;
+        Some(OwnerRule {
+          matcher: pattern_matcher(pattern)?,
+          owners: parts.map(str::to_string).collect(),
+        })
+      })
+      .collect();
+    Self { rules }
+  }
+
+  /// Owners of `path` (last matching line wins); empty if unowned.
+  pub fn owners_of(&self, path: &str) -> &[String] {
+    self
+      .rules
+      .iter()
+      .rev()
+      .find(|r| r.matcher.is_match(path))
+      .map_or(&[], |r| r.owners.as_slice())
+  }
+}
+
+/// CODEOWNERS pattern → globs for the path itself and everything below it.
+/// `*` never crosses a `/`, so `docs/*` owns only direct children of `docs/`.
+fn pattern_matcher(pattern: &str) -> Option<GlobSet> {
+  let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+  let dir_only = pattern.ends_with('/');
+  let mut glob = pattern.trim_matches('/').to_string();
+  if glob.is_empty() {
+    return None;
+  }
+  if !anchored && glob != "*" {
+    glob = format!("**/{}", glob);
+  }
+  let mut set = GlobSetBuilder::new();
+  let mut add = |g: &str| -> Option<()> {
+    let glob = GlobBuilder::new(g).literal_separator(true).build().ok()?;
+    set.add(glob);
+    Some(())
+  };
+  if glob == "*" {
+    // `*` alone owns every file in the repo.
+    add("**")?;
+  } else {
+    if !dir_only {
+      add(&glob)?;
+    }
+    if !glob.ends_with("/*") {
+      add(&format!("{}/**", glob))?;
+    }
+  }
+  set.build().ok()
+}
+
+/// Owners of every changed path, in input order.
+pub fn map_owners(owners: &CodeOwners, files: &[String]) -> Vec<FileOwners> {
+  files
+    .iter()
+    .map(|f| FileOwners {
+      path: f.clone(),
+      owners: owners.owners_of(f).to_vec(),
+    })
+    .collect()
+}
+
+/// Every owner touched by the change, sorted and deduplicated.
+pub fn affected_owners(file_owners: &[FileOwners]) -> Vec<String> {
+  let mut out: Vec<String> = file_owners
+    .iter()
+    .flat_map(|f| f.owners.iter().cloned())
+    .collect();
+  out.sort();
+  out.dedup();
+  out
+}
+
+/// `cross_owner` and `unowned` hits. Excluded files (generated, vendored,
+/// lockfiles) don't count: nobody reviews those by ownership.
+pub fn ownership_hits(file_owners: &[FileOwners], excluded: &[ExcludedFile]) -> Vec<FlagHit> {
+  let counted: Vec<&FileOwners> = file_owners
+    .iter()
+    .filter(|f| !excluded.iter().any(|e| e.path == f.path))
+    .collect();
+  let mut hits = Vec::new();
+
+  let mut groups: Vec<&[String]> = Vec::new();
+  for f in counted.iter().filter(|f| !f.owners.is_empty()) {
+    if !groups.contains(&f.owners.as_slice()) {
+      groups.push(&f.owners);
+    }
+  }
+  if groups.len() >= 2 {
+    let mut hit = if groups.len() >= 3 {
+      FlagHit::new(
+        RiskFlag::CrossOwner,
+        "codeowners-many",
+        MANY_OWNERS_WEIGHT,
+        "Change spans three or more code owner groups",
+      )
+    } else {
+      FlagHit::new(
+        RiskFlag::CrossOwner,
+        "codeowners-multiple",
+        CROSS_OWNER_WEIGHT,
+        "Change spans two code owner groups",
+      )
+    };
+    // First path of each owner group, so the explanation names a boundary.
+    hit.paths = groups
+      .iter()
+      .filter_map(|g| counted.iter().find(|f| f.owners.as_slice() == *g))
+      .map(|f| f.path.clone())
+      .collect();
+    hits.push(hit);
+  }
+
+  let unowned: Vec<String> = counted
+    .iter()
+    .filter(|f| f.owners.is_empty())
+    .map(|f| f.path.clone())
+    .collect();
+  if !unowned.is_empty() {
+    let mut hit = FlagHit::new(
+      RiskFlag::Unowned,
+      "codeowners-unowned",
+      UNOWNED_WEIGHT,
+      "Path has no code owner",
+    );
+    hit.paths = unowned;
+    hits.push(hit);
+  }
+  hits
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CODEOWNERS: &str = "\
+# Default owners
+*                     @pushlog/core
+/server/              @pushlog/backend
+*.sql                 @pushlog/dba @alice
+docs/                 @pushlog/docs
+/server/risk-engine/  @pushlog/risk
+/server/generated/
+/client/public/*      @pushlog/design
+";
+
+  fn files(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|s| s.to_string()).collect()
+  }
+
+  #[test]
+  fn last_matching_line_wins() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    assert_eq!(owners.owners_of("client/src/App.tsx"), ["@pushlog/core"]);
+    assert_eq!(owners.owners_of("server/routes.ts"), ["@pushlog/backend"]);
+    assert_eq!(
+      owners.owners_of("server/migrations/001.sql"),
+      ["@pushlog/dba", "@alice"]
+    );
+    assert_eq!(owners.owners_of("client/docs/a.md"), ["@pushlog/docs"]);
+    assert_eq!(
+      owners.owners_of("server/risk-engine/src/lib.rs"),
+      ["@pushlog/risk"]
+    );
+    assert!(owners.owners_of("server/generated/api.ts").is_empty());
+  }
+
+  #[test]
+  fn star_does_not_cross_directories() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    assert_eq!(
+      owners.owners_of("client/public/logo.svg"),
+      ["@pushlog/design"]
+    );
+    assert_eq!(
+      owners.owners_of("client/public/icons/logo.svg"),
+      ["@pushlog/core"]
+    );
+  }
+
+  #[test]
+  fn flags_cross_owner_and_unowned_changes() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    let mapped = map_owners(
+      &owners,
+      &files(&[
+        "server/routes.ts",
+        "server/db.ts",
+        "server/risk-engine/src/lib.rs",
+        "server/generated/api.ts",
+      ]),
+    );
+    assert_eq!(
+      affected_owners(&mapped),
+      vec!["@pushlog/backend", "@pushlog/risk"]
+    );
+    let hits = ownership_hits(&mapped, &[]);
+    let flags: Vec<(&str, i32)> = hits.iter().map(|h| (h.flag.as_str(), h.weight)).collect();
+    assert_eq!(flags, vec![("cross_owner", 5), ("unowned", 5)]);
+    assert_eq!(
+      hits[0].paths,
+      vec!["server/routes.ts", "server/risk-engine/src/lib.rs"]
+    );
+    assert_eq!(hits[1].paths, vec!["server/generated/api.ts"]);
+  }
+
+  #[test]
+  fn single_owner_change_raises_nothing() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    let mapped = map_owners(&owners, &files(&["server/a.ts", "server/b.ts"]));
+    assert!(ownership_hits(&mapped, &[]).is_empty());
+  }
+}
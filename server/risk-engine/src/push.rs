@@ -0,0 +1,131 @@
+//! Multi-commit pushes: per-commit scores and a push-level rollup.
+
+use crate::flags::RiskFlag;
+use crate::types::{CommitInput, CommitScore, FlagHit, Input, Output, PushRollup};
+
+/// Commits at or above this score are listed in `PushRollup::risky_commits`.
+pub const RISKY_COMMIT_SCORE: u8 = 50;
+
+/// Single-commit input for one commit of a push (no nested commits).
+/// `file_changes` describe the push as a whole, so commits rely on their
+/// diff's rename headers instead.
+pub fn commit_to_input(push: &Input, commit: &CommitInput) -> Input {
+  Input {
+    commit_message: commit.message.clone(),
+    files_changed: commit.files.clone(),
+    additions: commit.additions,
+    deletions: commit.deletions,
+    file_changes: Vec::new(),
+    diff_text: commit.diff_text.clone(),
+    gitattributes: push.gitattributes.clone(),
+    codeowners: push.codeowners.clone(),
+    history: push.history.clone(),
+    commits: Vec::new(),
+  }
+}
+
+/// Compact per-commit view of a full output.
+pub fn commit_score(commit: &CommitInput, out: Output) -> CommitScore {
+  CommitScore {
+    id: commit.id.clone(),
+    impact_score: out.impact_score,
+    risk_flags: out.risk_flags,
+    change_type_tags: out.change_type_tags,
+    reverted_commits: out.reverted_commits,
+    commit_type: out.commit_type,
+    breaking_change: out.breaking_change,
+  }
+}
+
+/// Push-level summary: the riskiest commit stays visible even when the
+/// other commits are chores.
+pub fn rollup(commits: &[CommitScore]) -> PushRollup {
+  let riskiest = commits
+    .iter()
+    .enumerate()
+    // Earliest commit wins ties.
+    .max_by_key(|(i, c)| (c.impact_score, std::cmp::Reverse(*i)))
+    .map(|(_, c)| c);
+  let total: u32 = commits.iter().map(|c| c.impact_score as u32).sum();
+  PushRollup {
+    commit_count: commits.len(),
+    max_impact_score: riskiest.map(|c| c.impact_score).unwrap_or(0),
+    mean_impact_score: if commits.is_empty() {
+      0
+    } else {
+      (total / commits.len() as u32) as u8
+    },
+    riskiest_commit: riskiest.map(|c| c.id.clone()),
+    risky_commits: commits
+      .iter()
+      .filter(|c| c.impact_score >= RISKY_COMMIT_SCORE)
+      .map(|c| c.id.clone())
+      .collect(),
+  }
+}
+
+/// `risky_commit` hit when the riskiest commit outscores the push; its
+/// weight and contribution are the points it adds to `push_score`.
+pub fn risky_commit_hit(rollup: &PushRollup, push_score: u8) -> Option<FlagHit> {
+  let id = rollup.riskiest_commit.as_deref()?;
+  let gap = rollup.max_impact_score as i32 - push_score as i32;
+  if gap <= 0 {
+    return None;
+  }
+  let mut hit = FlagHit::new(
+    RiskFlag::RiskyCommit,
+    "riskiest-commit",
+    gap,
+    &format!("Commit {} scores {}", id, rollup.max_impact_score),
+  );
+  hit.contribution = gap;
+  Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn score(id: &str, impact_score: u8) -> CommitScore {
+    CommitScore {
+      id: id.to_string(),
+      impact_score,
+      risk_flags: Vec::new(),
+      change_type_tags: Vec::new(),
+      reverted_commits: Vec::new(),
+      commit_type: None,
+      breaking_change: false,
+    }
+  }
+
+  #[test]
+  fn rollup_surfaces_riskiest_commit() {
+    let r = rollup(&[
+      score("a", 10),
+      score("b", 70),
+      score("c", 12),
+      score("d", 70),
+    ]);
+    assert_eq!(r.commit_count, 4);
+    assert_eq!(r.max_impact_score, 70);
+    assert_eq!(r.riskiest_commit.as_deref(), Some("b"));
+    assert_eq!(r.mean_impact_score, 40);
+    assert_eq!(r.risky_commits, vec!["b", "d"]);
+  }
+
+  #[test]
+  fn risky_commit_hit_carries_the_gap() {
+    let r = rollup(&[score("a", 10), score("b", 70)]);
+    let hit = risky_commit_hit(&r, 55).unwrap();
+    assert_eq!((hit.weight, hit.contribution), (15, 15));
+    assert_eq!(hit.explanation, "Commit b scores 70");
+    assert!(risky_commit_hit(&r, 70).is_none());
+  }
+
+  #[test]
+  fn rollup_of_no_commits_is_empty() {
+    let r = rollup(&[]);
+    assert_eq!((r.commit_count, r.max_impact_score), (0, 0));
+    assert!(r.riskiest_commit.is_none());
+  }
+}
@@ -0,0 +1,107 @@
+//! Renames and moves, from `file_changes` statuses and diff `rename from` /
+//! `rename to` headers.
+//!
+//! A pure rename (no edited lines) doesn't count toward the file factor and
+//! is never a hotspot; neither is the old path of any rename, when a caller
+//! lists it as a removed file.
+
+use crate::diff::FileDiff;
+use crate::types::{FileChange, RenamedFile};
+
+/// Renamed files: `file_changes` entries with a `previous_path` (copies
+/// aside), then diff renames not already listed. Churn comes from the
+/// entry's counts, else from the diff.
+pub fn detect(changes: &[FileChange], diffs: &[FileDiff]) -> Vec<RenamedFile> {
+  let diff_churn = |path: &str| {
+    diffs
+      .iter()
+      .find(|d| d.path == path)
+      .map_or(0, FileDiff::churn)
+  };
+  let mut out: Vec<RenamedFile> = Vec::new();
+  for c in changes {
+    let Some(previous) = c.previous_path.as_deref().filter(|p| *p != c.path) else {
+      continue;
+    };
+    // A copy leaves the original in place.
+    if c.status.as_deref() == Some("copied") {
+      continue;
+    }
+    let churn = match (c.additions, c.deletions) {
+      (None, None) => diff_churn(&c.path),
+      (a, d) => a.unwrap_or(0).saturating_add(d.unwrap_or(0)),
+    };
+    out.push(renamed(&c.path, previous, churn));
+  }
+  for d in diffs {
+    let Some(previous) = d.old_path.as_deref().filter(|p| *p != d.path) else {
+      continue;
+    };
+    if !out.iter().any(|r| r.path == d.path) {
+      out.push(renamed(&d.path, previous, d.churn()));
+    }
+  }
+  out
+}
+
+/// Whether `path` is only a move: a pure rename's new path, or the old path
+/// of any rename.
+pub fn is_move_only(renamed: &[RenamedFile], path: &str) -> bool {
+  renamed
+    .iter()
+    .any(|r| (r.pure && r.path == path) || r.previous_path == path)
+}
+
+fn renamed(path: &str, previous_path: &str, churn: u32) -> RenamedFile {
+  RenamedFile {
+    path: path.to_string(),
+    previous_path: previous_path.to_string(),
+    churn,
+    pure: churn == 0,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diff::parse_unified_diff;
+
+  fn change(path: &str, status: &str, previous: &str, churn: Option<u32>) -> FileChange {
+    FileChange {
+      path: path.to_string(),
+      status: Some(status.to_string()),
+      previous_path: Some(previous.to_string()),
+      additions: churn,
+      deletions: churn.map(|_| 0),
+    }
+  }
+
+  #[test]
+  fn merges_statuses_and_diff_headers() {
+    let diffs = parse_unified_diff(
+      "diff --git a/old/b.ts b/new/b.ts\nsimilarity index 80%\nrename from old/b.ts\nrename to new/b.ts\n--- a/old/b.ts\n+++ b/new/b.ts\n@@ -1 +1 @@\n-x\n+y\ndiff --git a/old/c.ts b/new/c.ts\nsimilarity index 100%\nrename from old/c.ts\nrename to new/c.ts\n",
+    );
+    let changes = vec![
+      change("new/a.ts", "renamed", "old/a.ts", Some(0)),
+      change("new/b.ts", "renamed", "old/b.ts", None),
+      change("copy/d.ts", "copied", "old/d.ts", Some(0)),
+    ];
+    let renamed = detect(&changes, &diffs);
+    let summary: Vec<(&str, u32, bool)> = renamed
+      .iter()
+      .map(|r| (r.path.as_str(), r.churn, r.pure))
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        ("new/a.ts", 0, true),
+        ("new/b.ts", 2, false),
+        ("new/c.ts", 0, true),
+      ]
+    );
+    assert!(is_move_only(&renamed, "new/a.ts"));
+    assert!(is_move_only(&renamed, "old/b.ts"));
+    assert!(!is_move_only(&renamed, "new/b.ts"));
+    assert!(!is_move_only(&renamed, "copy/d.ts"));
+  }
+}
@@ -0,0 +1,34 @@
+//! `RiskReport`: the engine's output for library callers, with typed flags.
+
+use crate::flags::RiskFlag;
+use crate::types::{FlagHit, Output};
+
+/// Result of scoring one push. `flags` are `output.risk_flags`; `output`
+/// holds everything else (hits, findings, per-commit scores, ...).
+#[derive(Debug)]
+pub struct RiskReport {
+  pub impact_score: u8,
+  pub flags: Vec<RiskFlag>,
+  pub output: Output,
+}
+
+impl RiskReport {
+  pub fn has_flag(&self, flag: &RiskFlag) -> bool {
+    self.flags.contains(flag)
+  }
+
+  /// The hit that raised `flag` (or applied it, for discounts).
+  pub fn hit(&self, flag: &RiskFlag) -> Option<&FlagHit> {
+    self.output.flag_hits.iter().find(|h| &h.flag == flag)
+  }
+}
+
+impl From<Output> for RiskReport {
+  fn from(output: Output) -> Self {
+    Self {
+      impact_score: output.impact_score,
+      flags: output.risk_flags.clone(),
+      output,
+    }
+  }
+}
@@ -0,0 +1,70 @@
+//! Revert and hotfix detection from commit messages.
+//!
+//! `git revert` writes `Revert "<subject>"` and a "This reverts commit
+//! <sha>." body line; the SHAs let callers link a revert to what it undid.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+fn reverts_commit() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r"(?i)\bthis reverts commit ([0-9a-f]{7,40})\b").expect("valid revert pattern")
+  })
+}
+
+fn hotfix_word() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r"(?i)\b(?:hot[- ]?fix(?:es|ed)?|urgent|roll[- ]?back(?:s|ed)?)\b")
+      .expect("valid hotfix pattern")
+  })
+}
+
+/// Lowercased SHAs named by "This reverts commit <sha>" lines, in order.
+pub fn reverted_commits(message: &str) -> Vec<String> {
+  let mut out: Vec<String> = Vec::new();
+  for caps in reverts_commit().captures_iter(message) {
+    let sha = caps[1].to_lowercase();
+    if !out.contains(&sha) {
+      out.push(sha);
+    }
+  }
+  out
+}
+
+/// A `Revert "..."` header, or a body naming the reverted commit.
+pub fn is_revert(message: &str) -> bool {
+  let header = message.lines().next().unwrap_or("").trim_start();
+  header.starts_with("Revert \"") || reverts_commit().is_match(message)
+}
+
+/// Mentions a hotfix, an urgent fix or a rollback anywhere in the message.
+pub fn is_hotfix(message: &str) -> bool {
+  hotfix_word().is_match(message)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn git_revert_message_names_the_reverted_commit() {
+    let msg = "Revert \"feat: add billing\"\n\nThis reverts commit 3F2A9C1D0B.\n";
+    assert!(is_revert(msg));
+    assert_eq!(reverted_commits(msg), vec!["3f2a9c1d0b"]);
+    assert!(is_revert("Revert \"fix: x\""));
+    assert!(!is_revert("Reverted layout tweaks in the header"));
+    assert!(reverted_commits("this reverts commit xyz").is_empty());
+  }
+
+  #[test]
+  fn hotfix_keywords_are_whole_words() {
+    assert!(is_hotfix("Hotfix: null check in webhook"));
+    assert!(is_hotfix("fix(api): urgent patch for login loop"));
+    assert!(is_hotfix("Roll back the cache change"));
+    assert!(is_hotfix("rollback deploy 42"));
+    assert!(!is_hotfix("shotfixture cleanup"));
+    assert!(!is_hotfix("urgently needed docs"));
+  }
+}
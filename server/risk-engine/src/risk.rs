@@ -1,94 +1,113 @@
-//! Risk flags derived from file path patterns.
+//! Risk flags derived from file path patterns (see `rules`).
 
-use std::collections::HashSet;
+use crate::classify::FileClass;
+use crate::rules::RuleSet;
+use crate::types::FlagHit;
 
-/// Risk flags from file path patterns (lowercase, no duplicates).
-pub fn compute_risk_flags(files: &[String]) -> Vec<String> {
-  let mut flags = HashSet::new();
-  for path in files {
-    let p = path.to_lowercase();
-    if p.contains("package-lock.json")
-      || p.contains("yarn.lock")
-      || p.contains("cargo.lock")
-      || p.ends_with("go.sum")
-      || p.ends_with("go.mod")
-      || p.contains("pnpm-lock")
-    {
-      flags.insert("deps".to_string());
-    }
-    if p.contains("migration")
-      || p.contains("schema")
-      || p.contains("prisma")
-      || p.contains("/migrations/")
-    {
-      flags.insert("migration".to_string());
-    }
-    if p.contains("auth")
-      || p.contains("jwt")
-      || p.contains("oauth")
-      || p.contains("session")
-      || p.contains("/acl")
-      || p.contains("permission")
-    {
-      flags.insert("auth".to_string());
-    }
-    if p.contains(".env")
-      || p.contains("config")
-      || p.contains("secrets")
-      || p.contains("keys")
-      || p.contains("credential")
-    {
-      flags.insert("config".to_string());
-    }
-    if p.contains("secret")
-      || p.contains("password")
-      || p.contains("api_key")
-      || p.contains("apikey")
-    {
-      flags.insert("secrets".to_string());
+/// Risk flags from file path patterns: one hit per flag, sorted by flag.
+pub fn compute_risk_flags(rules: &RuleSet, files: &[FileClass]) -> Vec<FlagHit> {
+  rules.match_flags(files)
+}
+
+/// Add a hit from another analyzer. If the flag is already set, the higher
+/// weight wins and paths are unioned, same as for rule matches.
+pub fn merge_hit(hits: &mut Vec<FlagHit>, hit: FlagHit) {
+  match hits.iter_mut().find(|h| h.flag == hit.flag) {
+    Some(existing) => {
+      if hit.weight > existing.weight {
+        existing.rule = hit.rule;
+        existing.weight = hit.weight;
+        existing.explanation = hit.explanation;
+      }
+      for p in hit.paths {
+        if !existing.paths.contains(&p) {
+          existing.paths.push(p);
+        }
+      }
     }
-    if p.contains("payment")
-      || p.contains("stripe")
-      || p.contains("billing")
-      || p.contains("invoice")
-    {
-      flags.insert("payment".to_string());
+    None => {
+      hits.push(hit);
+      hits.sort_by(|a, b| a.flag.cmp(&b.flag));
     }
   }
-  let mut v: Vec<String> = flags.into_iter().collect();
-  v.sort();
-  v
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::classify::classify;
+  use crate::flags::RiskFlag;
+
+  fn flags(files: &[&str]) -> Vec<String> {
+    let files: Vec<FileClass> = files.iter().map(|p| classify(p)).collect();
+    compute_risk_flags(&RuleSet::builtin(), &files)
+      .into_iter()
+      .map(|h| h.flag.to_string())
+      .collect()
+  }
 
   #[test]
   fn risk_flags_deps_from_lockfiles() {
-    let files = vec!["package-lock.json".to_string(), "src/foo.ts".to_string()];
-    let flags = compute_risk_flags(&files);
+    let flags = flags(&["package-lock.json", "src/foo.ts"]);
     assert!(flags.contains(&"deps".to_string()));
   }
 
   #[test]
   fn risk_flags_auth_from_path() {
-    let files = vec!["src/auth/jwt.go".to_string()];
-    let flags = compute_risk_flags(&files);
+    let flags = flags(&["src/auth/jwt.go"]);
     assert!(flags.contains(&"auth".to_string()));
   }
 
   #[test]
   fn risk_flags_migration_from_prisma() {
-    let files = vec!["prisma/schema.prisma".to_string()];
-    let flags = compute_risk_flags(&files);
+    let flags = flags(&["prisma/schema.prisma"]);
     assert!(flags.contains(&"migration".to_string()));
   }
 
+  #[test]
+  fn risk_flags_crypto_from_path() {
+    assert_eq!(flags(&["lib/encryption/aes.rs"]), vec!["crypto"]);
+    assert_eq!(flags(&["server/tls/x509.go"]), vec!["crypto"]);
+  }
+
+  #[test]
+  fn risk_flags_ci_and_infra_from_roles() {
+    assert_eq!(
+      flags(&["deploy-production.sh", "Dockerfile.production"]),
+      vec!["infra"]
+    );
+    assert_eq!(
+      flags(&[".github/workflows/ci.yml", "charts/api/values.yaml"]),
+      vec!["ci", "infra"]
+    );
+  }
+
   #[test]
   fn risk_flags_empty_for_plain_paths() {
-    let files = vec!["README.md".to_string(), "src/utils.ts".to_string()];
-    let flags = compute_risk_flags(&files);
+    let flags = flags(&["README.md", "src/utils.ts"]);
     assert!(flags.is_empty());
   }
+
+  #[test]
+  fn merge_hit_keeps_highest_weight_and_unions_paths() {
+    let files = vec![classify("package-lock.json")];
+    let mut hits = compute_risk_flags(&RuleSet::builtin(), &files);
+    let mut dep = FlagHit::new(
+      RiskFlag::Deps,
+      "dependency-added-or-major",
+      15,
+      "New dependency",
+    );
+    dep.paths.push("package.json".to_string());
+    merge_hit(&mut hits, dep);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].weight, 15);
+    assert_eq!(hits[0].paths, vec!["package-lock.json", "package.json"]);
+  }
+
+  #[test]
+  fn risk_flags_skip_docs_about_risky_areas() {
+    assert!(flags(&["docs/config.md", "docs/auth/oauth.md"]).is_empty());
+    assert_eq!(flags(&["config.yaml"]), vec!["config"]);
+  }
 }
@@ -0,0 +1,361 @@
+//! HTTP route extraction from diffs, so pushes can be joined with incidents'
+//! `api_route`.
+//!
+//! Recognized registrations: Express/Koa/Fastify/Hono/gin style
+//! `app.get("/path", ...)`, axum/Flask `.route("/path", ...)`, actix and
+//! FastAPI/Flask decorators (`#[get("/path")]`, `@app.post("/path")`) and Go
+//! `HandleFunc("/path", ...)` (with Go 1.22 `"GET /path"` patterns).
+//!
+//! A registration line added or removed is an added/removed/changed route.
+//! An edit below a registration visible in the hunk (its context or the hunk
+//! header) marks that route's handler as modified, unless the registration
+//! ends on its own line (`app.get("/x", handler);`).
+
+use crate::classify::{classify, FileClass, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::types::{ApiChange, ApiChangeKind, ApiKind, ExcludedFile, RouteChange, RouteChangeKind};
+use regex::Regex;
+use std::sync::OnceLock;
+
+const METHODS: &str = "get|post|put|patch|delete|options|head|all";
+
+/// `app.get("/x"` / `router.POST("/x"` / `@app.post("/x"` / `#[get("/x"`.
+/// The receiver must be `app`, `server`, `fastify`, `r` or a `*router`, so
+/// HTTP client calls (`axios.get("/api/x")`, `api.post(...)`) don't count.
+fn method_call() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(&format!(
+      r#"(?i)(?:\b(?:app|server|fastify|r|\w*router)\.|#\[)({})\(\s*["'`](/[^"'`]*)["'`]"#,
+      METHODS
+    ))
+    .expect("valid route pattern")
+  })
+}
+
+/// `.route("/x", get(h).post(h2))` (axum) / `@app.route("/x", methods=[...])` (Flask).
+fn route_call() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r#"\.route\(\s*["'](/[^"']*)["'](.*)"#).expect("valid route pattern")
+  })
+}
+
+/// Methods named later on a `.route(...)` line: `get(` / `.post(` / `"PUT"`.
+fn route_methods() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(&format!(
+      r#"(?i)\b({})\s*\(|["']({})["']"#,
+      METHODS, METHODS
+    ))
+    .expect("valid route pattern")
+  })
+}
+
+/// Go `HandleFunc("/x"` / `Handle("GET /x"`.
+fn go_handle() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r#"\bHandle(?:Func)?\(\s*"(?:([A-Z]+)\s+)?(/[^"]*)""#).expect("valid route pattern")
+  })
+}
+
+/// One route registered on a line: (METHOD, path). `ANY` when the line
+/// doesn't say.
+fn registrations(text: &str) -> Vec<(String, String)> {
+  if let Some(c) = method_call().captures(text) {
+    return vec![(c[1].to_uppercase(), c[2].to_string())];
+  }
+  if let Some(c) = route_call().captures(text) {
+    let path = c[1].to_string();
+    let mut methods: Vec<String> = route_methods()
+      .captures_iter(&c[2])
+      .filter_map(|m| m.get(1).or(m.get(2)))
+      .map(|m| m.as_str().to_uppercase())
+      .collect();
+    methods.dedup();
+    if methods.is_empty() {
+      methods.push("ANY".to_string());
+    }
+    return methods.into_iter().map(|m| (m, path.clone())).collect();
+  }
+  if let Some(c) = go_handle().captures(text) {
+    let method = c.get(1).map_or("ANY", |m| m.as_str());
+    return vec![(method.to_string(), c[2].to_string())];
+  }
+  Vec::new()
+}
+
+/// Whether a registration ends on its own line: its parentheses balance, as
+/// in `app.get("/health", healthHandler);`. Decorators never do, since the
+/// handler follows them.
+fn self_contained(text: &str) -> bool {
+  let t = text.trim_start();
+  if t.starts_with('@') || t.starts_with("#[") {
+    return false;
+  }
+  let mut depth = 0i32;
+  let mut quote: Option<char> = None;
+  for c in t.chars() {
+    match (quote, c) {
+      (Some(q), c) if c == q => quote = None,
+      (Some(_), _) => {}
+      (None, '"' | '\'' | '`') => quote = Some(c),
+      (None, '(') => depth += 1,
+      (None, ')') => depth -= 1,
+      _ => {}
+    }
+  }
+  depth <= 0
+}
+
+fn indent(text: &str) -> usize {
+  text.len() - text.trim_start().len()
+}
+
+/// A registration seen in a hunk, to attribute the edits below it.
+struct Open {
+  routes: Vec<(String, String)>,
+  indent: usize,
+  line: u32,
+}
+
+/// Routes added, removed, re-registered or with a modified handler, in
+/// source files (test servers, generated and vendored files are skipped).
+pub fn detect(
+  diffs: &[FileDiff],
+  classes: &[FileClass],
+  excluded: &[ExcludedFile],
+) -> Vec<RouteChange> {
+  let mut out = Vec::new();
+  for file in diffs {
+    if file.binary || excluded.iter().any(|e| e.path == file.path) {
+      continue;
+    }
+    let role = classes
+      .iter()
+      .find(|c| c.path == file.path)
+      .map_or_else(|| classify(&file.path).role, |c| c.role);
+    if role != FileRole::Source {
+      continue;
+    }
+    let mut added: Vec<(String, String, String, u32)> = Vec::new();
+    let mut removed: Vec<(String, String, String, u32)> = Vec::new();
+    let mut modified: Vec<(String, String, u32)> = Vec::new();
+    for hunk in &file.hunks {
+      let mut open: Option<Open> = None;
+      let section = registrations(&hunk.section);
+      if !section.is_empty() && !self_contained(&hunk.section) {
+        open = Some(Open {
+          routes: section,
+          indent: indent(&hunk.section),
+          line: hunk.new_start,
+        });
+      }
+      for line in &hunk.lines {
+        let found = registrations(&line.text);
+        if !found.is_empty() {
+          let line_no = line.new_line.or(line.old_line).unwrap_or(0);
+          for (method, route) in &found {
+            let entry = (
+              method.clone(),
+              route.clone(),
+              line.text.trim().to_string(),
+              line_no,
+            );
+            match line.kind {
+              LineKind::Added => added.push(entry),
+              LineKind::Removed => removed.push(entry),
+              LineKind::Context => {}
+            }
+          }
+          open = (!self_contained(&line.text)).then(|| Open {
+            routes: found,
+            indent: indent(&line.text),
+            line: line_no,
+          });
+          continue;
+        }
+        let closes = open.as_ref().is_some_and(|o| {
+          let t = line.text.trim_start();
+          !t.is_empty() && indent(&line.text) <= o.indent && t.starts_with(['}', ')'])
+        });
+        if line.kind != LineKind::Context {
+          if let Some(o) = &open {
+            for (method, route) in &o.routes {
+              if !modified.iter().any(|(m, r, _)| m == method && r == route) {
+                modified.push((method.clone(), route.clone(), o.line));
+              }
+            }
+          }
+        }
+        if closes {
+          open = None;
+        }
+      }
+    }
+
+    let mut push = |method: &str, route: &str, change: RouteChangeKind, line: u32| {
+      out.push(RouteChange {
+        path: file.path.clone(),
+        method: method.to_string(),
+        route: route.to_string(),
+        change,
+        line,
+      });
+    };
+    let same = |a: &(String, String, String, u32), m: &str, r: &str| a.0 == m && a.1 == r;
+    for a in &added {
+      match removed.iter().find(|r| same(r, &a.0, &a.1)) {
+        Some(r) if r.2 == a.2 => {}
+        Some(_) => push(&a.0, &a.1, RouteChangeKind::Changed, a.3),
+        None => push(&a.0, &a.1, RouteChangeKind::Added, a.3),
+      }
+    }
+    for r in &removed {
+      if !added.iter().any(|a| same(a, &r.0, &r.1)) {
+        push(&r.0, &r.1, RouteChangeKind::Removed, r.3);
+      }
+    }
+    for (method, route, line) in &modified {
+      let registered = added.iter().chain(&removed).any(|a| same(a, method, route));
+      if !registered {
+        push(method, route, RouteChangeKind::Modified, *line);
+      }
+    }
+  }
+  out
+}
+
+/// Registration changes as API surface entries ("GET /api/x", kind
+/// `route`); handler-body edits are not API changes.
+pub fn api_changes(routes: &[RouteChange]) -> Vec<ApiChange> {
+  routes
+    .iter()
+    .filter_map(|r| {
+      let change = match r.change {
+        RouteChangeKind::Added => ApiChangeKind::Added,
+        RouteChangeKind::Removed => ApiChangeKind::Removed,
+        RouteChangeKind::Changed => ApiChangeKind::Changed,
+        RouteChangeKind::Modified => return None,
+      };
+      Some(ApiChange {
+        path: r.path.clone(),
+        symbol: format!("{} {}", r.method, r.route),
+        kind: ApiKind::Route,
+        change,
+        line: r.line,
+      })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diff::parse_unified_diff;
+
+  fn routes(diff: &str) -> Vec<String> {
+    detect(&parse_unified_diff(diff), &[], &[])
+      .into_iter()
+      .map(|r| format!("{:?} {} {}", r.change, r.method, r.route))
+      .collect()
+  }
+
+  #[test]
+  fn express_registrations_and_handler_edits() {
+    let diff = r#"--- a/server/routes.ts
++++ b/server/routes.ts
+@@ -10,9 +10,9 @@ export async function registerRoutes(app: Express) {
+   app.get("/health", (req, res) => {
+-    res.send("ok");
++    res.json({ ok: true });
+   });
+-  app.post("/api/agents", authenticateToken, async (req, res) => {
++  app.post("/api/agents", authenticateToken, requireOrgMember, async (req, res) => {
+   });
+-  app.delete("/api/legacy", handler);
++  router.patch('/api/users/:id', updateUser);
+   const x = 1;
+"#;
+    assert_eq!(
+      routes(diff),
+      vec![
+        "Changed POST /api/agents",
+        "Added PATCH /api/users/:id",
+        "Removed DELETE /api/legacy",
+        "Modified GET /health",
+      ]
+    );
+  }
+
+  #[test]
+  fn one_line_registrations_own_no_later_edits() {
+    let diff = r#"--- a/server/routes.ts
++++ b/server/routes.ts
+@@ -10,3 +10,3 @@
+   app.get("/health", healthHandler);
+-const limit = 10;
++const limit = 20;
+--- a/server/app.ts
++++ b/server/app.ts
+@@ -20,2 +20,2 @@   app.get("/ready", (req, res) => res.send("ok"));
+-  const retries = 3;
++  const retries = 5;
+"#;
+    assert!(routes(diff).is_empty());
+  }
+
+  #[test]
+  fn axum_actix_flask_and_go_registrations() {
+    let diff = r#"--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,0 +1,3 @@
++        .route("/api/pushes", get(list_pushes).post(create_push))
++        .route("/api/ws", any_service)
++#[delete("/api/pushes/{id}")]
+--- a/app/api.py
++++ b/app/api.py
+@@ -1,0 +1,2 @@
++@app.route("/login", methods=["GET", "POST"])
++@router.post("/items")
+--- a/cmd/server.go
++++ b/cmd/server.go
+@@ -1,0 +1 @@
++	mux.HandleFunc("GET /api/health", health)
+"#;
+    assert_eq!(
+      routes(diff),
+      vec![
+        "Added GET /api/pushes",
+        "Added POST /api/pushes",
+        "Added ANY /api/ws",
+        "Added DELETE /api/pushes/{id}",
+        "Added GET /login",
+        "Added POST /login",
+        "Added POST /items",
+        "Added GET /api/health",
+      ]
+    );
+  }
+
+  #[test]
+  fn map_lookups_are_not_routes() {
+    let diff = "--- a/src/a.ts\n+++ b/src/a.ts\n@@ -1 +1 @@\n-const v = cache.get(\"key\");\n+const v = headers.get('x-id');\n";
+    assert!(routes(diff).is_empty());
+  }
+
+  #[test]
+  fn client_calls_are_not_routes() {
+    let diff = r#"--- a/client/src/lib/api.ts
++++ b/client/src/lib/api.ts
+@@ -1,0 +1,4 @@
++const res = await axios.get("/api/pushes");
++await api.post('/api/agents', body);
++this.http.delete(`/api/users/${id}`);
++usersRouter.get("/api/users", listUsers);
+"#;
+    assert_eq!(routes(diff), vec!["Added GET /api/users"]);
+  }
+}
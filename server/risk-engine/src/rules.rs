@@ -0,0 +1,347 @@
+//! Rule packs: path patterns → risk flags (with weight + explanation) and
+//! change-type tags. The built-in pack lives in `rules/default.toml`; an org
+//! can ship its own TOML or JSON pack next to the binary.
+//!
+//! Rules may also filter on the file's classified role (`roles` /
+//! `exclude_roles`), so e.g. `docs/auth.md` does not raise `auth`.
+
+use crate::classify::{FileClass, FileRole};
+use crate::error::EngineError;
+use crate::flags::{ChangeType, RiskFlag};
+use crate::types::FlagHit;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::{RegexSet, RegexSetBuilder};
+use serde::Deserialize;
+use std::path::Path;
+
+const BUILTIN_PACK: &str = include_str!("../rules/default.toml");
+
+/// Rule pack as written on disk (TOML or JSON).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RulePack {
+  #[serde(default)]
+  pub name: String,
+  /// When true (default), rules are added on top of the built-in pack.
+  #[serde(default = "default_true")]
+  pub extends_default: bool,
+  #[serde(default)]
+  pub rules: Vec<FlagRuleDef>,
+  #[serde(default)]
+  pub tags: Vec<TagRuleDef>,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+/// Path patterns that raise a risk flag.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlagRuleDef {
+  pub id: String,
+  /// A built-in flag name, or any other name for a custom flag.
+  pub flag: RiskFlag,
+  #[serde(default)]
+  pub weight: i32,
+  #[serde(default)]
+  pub explanation: String,
+  #[serde(flatten)]
+  pub paths: PathPatterns,
+}
+
+/// Path patterns that add a change-type tag.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagRuleDef {
+  pub id: String,
+  pub tag: ChangeType,
+  #[serde(flatten)]
+  pub paths: PathPatterns,
+}
+
+/// Which files a rule applies to. A file matches when its role passes the
+/// role filters and (if any globs/regex are given) its path matches one.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PathPatterns {
+  #[serde(default)]
+  pub globs: Vec<String>,
+  #[serde(default)]
+  pub regex: Vec<String>,
+  /// Only files with one of these roles.
+  #[serde(default)]
+  pub roles: Vec<FileRole>,
+  /// Never files with one of these roles.
+  #[serde(default)]
+  pub exclude_roles: Vec<FileRole>,
+}
+
+/// Compiled glob + regex patterns and role filters for one rule.
+#[derive(Debug, Clone)]
+struct PathMatcher {
+  globs: GlobSet,
+  regex: RegexSet,
+  has_patterns: bool,
+  roles: Vec<FileRole>,
+  exclude_roles: Vec<FileRole>,
+}
+
+impl PathMatcher {
+  fn compile(id: &str, patterns: &PathPatterns) -> Result<Self, EngineError> {
+    let has_patterns = !patterns.globs.is_empty() || !patterns.regex.is_empty();
+    if !has_patterns && patterns.roles.is_empty() {
+      return Err(EngineError::rules(id, "rule has no globs, regex or roles"));
+    }
+    let mut set = GlobSetBuilder::new();
+    for g in &patterns.globs {
+      let glob = GlobBuilder::new(g)
+        .case_insensitive(true)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| EngineError::rules(id, &e.to_string()))?;
+      set.add(glob);
+    }
+    let globs = set
+      .build()
+      .map_err(|e| EngineError::rules(id, &e.to_string()))?;
+    let regex = RegexSetBuilder::new(&patterns.regex)
+      .case_insensitive(true)
+      .build()
+      .map_err(|e| EngineError::rules(id, &e.to_string()))?;
+    Ok(Self {
+      globs,
+      regex,
+      has_patterns,
+      roles: patterns.roles.clone(),
+      exclude_roles: patterns.exclude_roles.clone(),
+    })
+  }
+
+  fn is_match(&self, file: &FileClass) -> bool {
+    if self.exclude_roles.contains(&file.role) {
+      return false;
+    }
+    if !self.roles.is_empty() && !self.roles.contains(&file.role) {
+      return false;
+    }
+    !self.has_patterns || self.regex.is_match(&file.path) || self.globs.is_match(&file.path)
+  }
+}
+
+#[derive(Debug, Clone)]
+struct FlagRule {
+  id: String,
+  flag: RiskFlag,
+  weight: i32,
+  explanation: String,
+  matcher: PathMatcher,
+}
+
+#[derive(Debug, Clone)]
+struct TagRule {
+  tag: ChangeType,
+  matcher: PathMatcher,
+}
+
+/// Compiled, ready-to-evaluate rules (built-in pack plus any custom pack).
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+  flags: Vec<FlagRule>,
+  tags: Vec<TagRule>,
+}
+
+impl Default for RuleSet {
+  fn default() -> Self {
+    Self::builtin()
+  }
+}
+
+impl RuleSet {
+  /// The built-in pack (same rules the engine always shipped with).
+  pub fn builtin() -> Self {
+    let pack = parse_pack("builtin", BUILTIN_PACK, false).expect("built-in rule pack parses");
+    Self::compile(&pack).expect("built-in rule pack compiles")
+  }
+
+  /// Compile a pack; if it extends the default, built-in rules come first.
+  pub fn from_pack(pack: &RulePack) -> Result<Self, EngineError> {
+    let custom = Self::compile(pack)?;
+    if !pack.extends_default {
+      return Ok(custom);
+    }
+    let mut set = Self::builtin();
+    set.flags.extend(custom.flags);
+    set.tags.extend(custom.tags);
+    Ok(set)
+  }
+
+  /// Load a pack from a `.toml` or `.json` file.
+  pub fn load(path: &Path) -> Result<Self, EngineError> {
+    let origin = path.display().to_string();
+    let raw =
+      std::fs::read_to_string(path).map_err(|e| EngineError::rules(&origin, &e.to_string()))?;
+    let is_json = path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let pack = parse_pack(&origin, &raw, is_json)?;
+    Self::from_pack(&pack)
+  }
+
+  fn compile(pack: &RulePack) -> Result<Self, EngineError> {
+    let flags = pack
+      .rules
+      .iter()
+      .map(|r| {
+        Ok(FlagRule {
+          id: r.id.clone(),
+          flag: r.flag.clone(),
+          weight: r.weight,
+          explanation: r.explanation.clone(),
+          matcher: PathMatcher::compile(&r.id, &r.paths)?,
+        })
+      })
+      .collect::<Result<Vec<_>, EngineError>>()?;
+    let tags = pack
+      .tags
+      .iter()
+      .map(|t| {
+        Ok(TagRule {
+          tag: t.tag.clone(),
+          matcher: PathMatcher::compile(&t.id, &t.paths)?,
+        })
+      })
+      .collect::<Result<Vec<_>, EngineError>>()?;
+    Ok(Self { flags, tags })
+  }
+
+  /// One hit per flag: the highest-weight matching rule, plus every path
+  /// that matched any rule for that flag. Sorted by flag.
+  pub fn match_flags(&self, files: &[FileClass]) -> Vec<FlagHit> {
+    let mut hits: Vec<FlagHit> = Vec::new();
+    for rule in &self.flags {
+      let paths: Vec<&String> = files
+        .iter()
+        .filter(|f| rule.matcher.is_match(f))
+        .map(|f| &f.path)
+        .collect();
+      if paths.is_empty() {
+        continue;
+      }
+      let idx = match hits.iter().position(|h| h.flag == rule.flag) {
+        Some(i) => i,
+        None => {
+          hits.push(FlagHit::new(
+            rule.flag.clone(),
+            &rule.id,
+            rule.weight,
+            &rule.explanation,
+          ));
+          hits.len() - 1
+        }
+      };
+      let hit = &mut hits[idx];
+      if rule.weight > hit.weight {
+        hit.rule = rule.id.clone();
+        hit.weight = rule.weight;
+        hit.explanation = rule.explanation.clone();
+      }
+      for p in paths {
+        if !hit.paths.contains(p) {
+          hit.paths.push(p.clone());
+        }
+      }
+    }
+    hits.sort_by(|a, b| a.flag.cmp(&b.flag));
+    hits
+  }
+
+  /// Change-type tags implied by paths (unsorted, may repeat).
+  pub fn match_tags(&self, files: &[FileClass]) -> Vec<ChangeType> {
+    self
+      .tags
+      .iter()
+      .filter(|t| files.iter().any(|f| t.matcher.is_match(f)))
+      .map(|t| t.tag.clone())
+      .collect()
+  }
+}
+
+fn parse_pack(origin: &str, raw: &str, is_json: bool) -> Result<RulePack, EngineError> {
+  if is_json {
+    serde_json::from_str(raw).map_err(|e| EngineError::rules(origin, &e.to_string()))
+  } else {
+    toml::from_str(raw).map_err(|e| EngineError::rules(origin, &e.to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify;
+
+  fn files(paths: &[&str]) -> Vec<FileClass> {
+    paths.iter().map(|p| classify(p)).collect()
+  }
+
+  #[test]
+  fn builtin_pack_matches_legacy_rules() {
+    let hits = RuleSet::builtin().match_flags(&files(&["src/Auth/JWT.go", "package-lock.json"]));
+    let flags: Vec<&str> = hits.iter().map(|h| h.flag.as_str()).collect();
+    assert_eq!(flags, vec!["auth", "deps"]);
+    assert_eq!(hits[0].weight, 15);
+    assert_eq!(hits[0].paths, vec!["src/Auth/JWT.go"]);
+  }
+
+  #[test]
+  fn custom_toml_pack_extends_builtin_with_globs() {
+    let pack: RulePack = toml::from_str(
+      r#"
+      [[rules]]
+      id = "terraform"
+      flag = "infra"
+      weight = 12
+      explanation = "Terraform changed"
+      globs = ["infra/terraform/**"]
+      "#,
+    )
+    .unwrap();
+    let set = RuleSet::from_pack(&pack).unwrap();
+    let hits = set.match_flags(&files(&["infra/terraform/main.tf", "yarn.lock"]));
+    let flags: Vec<&str> = hits.iter().map(|h| h.flag.as_str()).collect();
+    assert_eq!(flags, vec!["deps", "infra"]);
+  }
+
+  #[test]
+  fn json_pack_can_replace_builtin() {
+    let pack = parse_pack(
+      "test.json",
+      r#"{"extends_default": false, "rules": [{"id": "billing", "flag": "payment", "weight": 20, "regex": ["^services/billing-v\\d+/"]}]}"#,
+      true,
+    )
+    .unwrap();
+    let set = RuleSet::from_pack(&pack).unwrap();
+    let hits = set.match_flags(&files(&["services/billing-v2/api.ts", "src/auth.ts"]));
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].flag.as_str(), hits[0].weight), ("payment", 20));
+  }
+
+  #[test]
+  fn invalid_pattern_is_an_error() {
+    let pack: RulePack =
+      toml::from_str("[[rules]]\nid = \"bad\"\nflag = \"x\"\nregex = [\"(\"]\n").unwrap();
+    let err = RuleSet::from_pack(&pack).unwrap_err();
+    assert!(err.to_string().contains("bad"));
+  }
+
+  #[test]
+  fn role_filters_narrow_path_rules() {
+    let set = RuleSet::builtin();
+    let hits = set.match_flags(&files(&["docs/auth.md", "docs/config.md"]));
+    assert!(hits.is_empty());
+    let pack: RulePack = toml::from_str(
+      "[[rules]]\nid = \"ci\"\nflag = \"ci\"\nweight = 8\nroles = [\"ci_workflow\"]\n",
+    )
+    .unwrap();
+    let set = RuleSet::from_pack(&pack).unwrap();
+    let hits = set.match_flags(&files(&[".github/workflows/deploy.yml", "src/ci.ts"]));
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].paths, vec![".github/workflows/deploy.yml"]);
+  }
+}
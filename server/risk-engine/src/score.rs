@@ -1,49 +1,118 @@
-//! Impact score, hotspot files, and human-readable explanations.
-
-use crate::types::Input;
-
-/// Impact 0–100: base from file count + churn, then bump for risk flags.
-pub fn compute_impact_score(input: &Input, risk_flags: &[String]) -> u8 {
-  let file_factor = (input.files_changed.len() as u32).min(30) * 2;
-  let churn = input.additions + input.deletions;
-  let churn_factor = (churn / 10).min(40);
-  let mut score = (file_factor + churn_factor) as i32;
-  for flag in risk_flags {
-    match flag.as_str() {
-      "auth" | "secrets" | "payment" => score += 15,
-      "migration" | "config" => score += 10,
-      "deps" => score += 5,
-      _ => {}
+//! Score features, hotspots, and human-readable explanations.
+
+use crate::diff::FileDiff;
+use crate::flags::ChangeType;
+use crate::history;
+use crate::renames;
+use crate::scorer::ScoreFeatures;
+use crate::types::{ExcludedFile, FileHistory, FlagHit, Hotspot, Input, RenamedFile};
+
+/// Scorer inputs: non-excluded file count and churn. Churn is the larger
+/// of the reported totals and the parsed diff counts (totals may be missing;
+/// diff text may be truncated). Excluded files (generated, vendored,
+/// lockfiles) count toward neither; their churn is subtracted from the
+/// totals when the diff tells us what it was. Pure renames and the old paths
+/// of renames aren't counted as files.
+pub fn compute_features(
+  input: &Input,
+  diffs: &[FileDiff],
+  excluded: &[ExcludedFile],
+  renamed: &[RenamedFile],
+) -> ScoreFeatures {
+  let is_excluded = |path: &str| excluded.iter().any(|e| e.path == path);
+  let files = input
+    .files_changed
+    .iter()
+    .filter(|f| !is_excluded(f) && !renames::is_move_only(renamed, f))
+    .count() as u32;
+  let (excluded_churn, diff_churn) = diffs.iter().fold((0, 0), |(ex, inc), d| {
+    if is_excluded(&d.path) {
+      (ex + d.churn(), inc)
+    } else {
+      (ex, inc + d.churn())
     }
+  });
+  let reported = input
+    .additions
+    .saturating_add(input.deletions)
+    .saturating_sub(excluded_churn);
+  // Every reported file is excluded or moved: nothing human-written left to count.
+  let reported = if files == 0 && !input.files_changed.is_empty() {
+    0
+  } else {
+    reported
+  };
+  ScoreFeatures {
+    files,
+    churn: reported.max(diff_churn),
+    listed_files: input.files_changed.len() as u32,
+    reported_churn: input.additions.saturating_add(input.deletions),
   }
-  score.min(100).max(0) as u8
 }
 
-/// Top N files to highlight (we don't have per-file churn; use order, cap at N).
-pub fn compute_hotspot_files(files: &[String], n: usize) -> Vec<String> {
-  files.iter().take(n).cloned().collect()
+/// Top N files to highlight, by churn × historical instability
+/// (`(1 + churn) × instability`, see `history`). Without history this is
+/// plain diff churn; ties (e.g. no diff data) keep the original order.
+/// Excluded files and pure renames are never hotspots.
+pub fn compute_hotspots(
+  files: &[String],
+  diffs: &[FileDiff],
+  excluded: &[ExcludedFile],
+  renamed: &[RenamedFile],
+  history: &[FileHistory],
+  n: usize,
+) -> Vec<Hotspot> {
+  let mut paths: Vec<&str> = files.iter().map(String::as_str).collect();
+  for d in diffs {
+    if !files.contains(&d.path) {
+      paths.push(d.path.as_str());
+    }
+  }
+  let mut ranked: Vec<Hotspot> = paths
+    .into_iter()
+    .filter(|f| !excluded.iter().any(|e| e.path == *f) && !renames::is_move_only(renamed, f))
+    .map(|f| {
+      let churn = diffs
+        .iter()
+        .find(|d| d.path == f)
+        .map_or(0, FileDiff::churn);
+      let h = history::lookup(history, f);
+      let score = (1 + churn) as f64 * history::instability(h);
+      Hotspot {
+        path: f.to_string(),
+        churn,
+        change_count: h.map_or(0, |h| h.change_count),
+        incident_count: h.map_or(0, |h| h.incident_count),
+        author_count: h.map_or(0, |h| h.author_count),
+        score: (score * 100.0).round() / 100.0,
+      }
+    })
+    .collect();
+  // Stable sort keeps the original order among equal scores.
+  ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+  ranked.truncate(n);
+  ranked
 }
 
-/// Short human-readable reasons.
-pub fn compute_explanations(risk_flags: &[String], change_type_tags: &[String]) -> Vec<String> {
-  let mut out = Vec::new();
-  for flag in risk_flags {
-    let s = match flag.as_str() {
-      "deps" => "Touched dependency lockfiles or package manifests",
-      "migration" => "Schema or migration changes",
-      "auth" => "Auth or permission-related files changed",
-      "config" => "Config or environment-related files changed",
-      "secrets" => "Possible secrets or credentials area",
-      "payment" => "Payment or billing-related code changed",
-      _ => continue,
-    };
-    out.push(s.to_string());
-  }
-  if change_type_tags.contains(&"tests".to_string()) {
-    out.push("Test files changed".to_string());
-  }
-  if change_type_tags.contains(&"docs".to_string()) {
-    out.push("Documentation changed".to_string());
+/// Short human-readable reasons: each flag's rule explanation with the file
+/// that triggered it, then tags.
+pub fn compute_explanations(flag_hits: &[FlagHit], change_type_tags: &[ChangeType]) -> Vec<String> {
+  let mut out: Vec<String> = flag_hits
+    .iter()
+    .filter(|h| !h.explanation.is_empty())
+    .map(|h| match h.paths.as_slice() {
+      [] => h.explanation.clone(),
+      [only] => format!("{} ({})", h.explanation, only),
+      [first, rest @ ..] => format!("{} ({} and {} more)", h.explanation, first, rest.len()),
+    })
+    .collect();
+  for tag in [ChangeType::Tests, ChangeType::Docs] {
+    if let Some(line) = tag
+      .explanation()
+      .filter(|_| change_type_tags.contains(&tag))
+    {
+      out.push(line.to_string());
+    }
   }
   out
 }
@@ -51,34 +120,58 @@ pub fn compute_explanations(risk_flags: &[String], change_type_tags: &[String])
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::flags::RiskFlag;
+  use crate::scorer::{Scorer, WeightedScorer};
   use crate::types::Input;
 
+  fn compute_impact_score(
+    input: &Input,
+    hits: &mut [FlagHit],
+    diffs: &[FileDiff],
+    excluded: &[ExcludedFile],
+  ) -> u8 {
+    WeightedScorer.score(&compute_features(input, diffs, excluded, &[]), hits)
+  }
+
+  fn paths(hotspots: &[Hotspot]) -> Vec<&str> {
+    hotspots.iter().map(|h| h.path.as_str()).collect()
+  }
+
+  fn hit(flag: &str, weight: i32) -> FlagHit {
+    FlagHit::new(RiskFlag::from_name(flag), flag, weight, "")
+  }
+
   fn make_input(files_len: usize, additions: u32, deletions: u32) -> Input {
     Input {
       commit_message: "test".to_string(),
       files_changed: (0..files_len).map(|i| format!("file{}.ts", i)).collect(),
       additions,
       deletions,
-      diff_text: None,
+      ..Input::default()
     }
   }
 
   #[test]
   fn impact_score_bounds_0_100() {
     let input = make_input(0, 0, 0);
-    let score = compute_impact_score(&input, &[]);
+    let score = compute_impact_score(&input, &mut [], &[], &[]);
     assert!(score <= 100);
     let input = make_input(50, 5000, 5000);
-    let score = compute_impact_score(&input, &["auth".to_string(), "secrets".to_string()]);
+    let score = compute_impact_score(&input, &mut [hit("auth", 15), hit("secrets", 15)], &[], &[]);
     assert!(score <= 100);
+    let input = make_input(1, u32::MAX, u32::MAX);
+    assert_eq!(
+      compute_features(&input, &[], &[], &[]).reported_churn,
+      u32::MAX
+    );
   }
 
   #[test]
   fn impact_score_increases_with_risk_flags() {
     let input = make_input(2, 10, 10);
-    let base = compute_impact_score(&input, &[]);
-    let with_deps = compute_impact_score(&input, &["deps".to_string()]);
-    let with_auth = compute_impact_score(&input, &["auth".to_string()]);
+    let base = compute_impact_score(&input, &mut [], &[], &[]);
+    let with_deps = compute_impact_score(&input, &mut [hit("deps", 5)], &[], &[]);
+    let with_auth = compute_impact_score(&input, &mut [hit("auth", 15)], &[], &[]);
     assert!(with_deps >= base);
     assert!(with_auth >= base);
   }
@@ -86,8 +179,99 @@ mod tests {
   #[test]
   fn hotspot_files_caps_at_n() {
     let files: Vec<String> = (0..20).map(|i| format!("f{}.ts", i)).collect();
-    let out = compute_hotspot_files(&files, 5);
+    let out = compute_hotspots(&files, &[], &[], &[], &[], 5);
     assert_eq!(out.len(), 5);
-    assert_eq!(out[0], "f0.ts");
+    assert_eq!(out[0].path, "f0.ts");
+  }
+
+  fn file_diff(path: &str, additions: u32, deletions: u32) -> FileDiff {
+    FileDiff {
+      path: path.to_string(),
+      additions,
+      deletions,
+      ..FileDiff::default()
+    }
+  }
+
+  #[test]
+  fn hotspot_files_ranked_by_diff_churn() {
+    let files: Vec<String> = vec!["a.ts".into(), "b.ts".into(), "c.ts".into()];
+    let diffs = vec![file_diff("b.ts", 40, 2), file_diff("c.ts", 5, 5)];
+    let out = compute_hotspots(&files, &diffs, &[], &[], &[], 3);
+    assert_eq!(paths(&out), vec!["b.ts", "c.ts", "a.ts"]);
+  }
+
+  #[test]
+  fn history_reorders_hotspots_by_instability() {
+    let files: Vec<String> = vec!["a.ts".into(), "b.ts".into(), "c.ts".into()];
+    let diffs = vec![file_diff("a.ts", 20, 0), file_diff("b.ts", 10, 0)];
+    let history = vec![
+      FileHistory {
+        path: "b.ts".to_string(),
+        change_count: 50,
+        incident_count: 2,
+        author_count: 8,
+      },
+      FileHistory {
+        path: "c.ts".to_string(),
+        change_count: 3,
+        incident_count: 0,
+        author_count: 1,
+      },
+    ];
+    let out = compute_hotspots(&files, &diffs, &[], &[], &history, 3);
+    assert_eq!(paths(&out), vec!["b.ts", "a.ts", "c.ts"]);
+    assert_eq!((out[0].churn, out[0].incident_count), (10, 2));
+  }
+
+  #[test]
+  fn impact_score_uses_diff_churn_when_totals_missing() {
+    let input = make_input(1, 0, 0);
+    let without = compute_impact_score(&input, &mut [], &[], &[]);
+    let with = compute_impact_score(&input, &mut [], &[file_diff("file0.ts", 200, 100)], &[]);
+    assert!(with > without);
+  }
+
+  #[test]
+  fn flag_contributions_sum_to_score_under_cap() {
+    let input = make_input(45, 2000, 0);
+    let mut hits = [hit("auth", 15), hit("deps", 5)];
+    let score = compute_impact_score(&input, &mut hits, &[], &[]);
+    // Base is 60 + 40 = 100, so no flag adds anything past the cap.
+    assert_eq!(score, 100);
+    assert_eq!((hits[0].contribution, hits[1].contribution), (0, 0));
+
+    let input = make_input(2, 10, 10);
+    let score = compute_impact_score(&input, &mut hits, &[], &[]);
+    assert_eq!((hits[0].contribution, hits[1].contribution), (15, 5));
+    assert_eq!(score, 4 + 2 + 15 + 5);
+  }
+
+  #[test]
+  fn explanations_name_the_triggering_file() {
+    let mut auth = FlagHit::new(RiskFlag::Auth, "auth-path", 15, "Auth changed");
+    auth.paths = vec!["src/auth/jwt.go".to_string(), "src/auth/acl.go".to_string()];
+    let out = compute_explanations(&[auth], &[]);
+    assert_eq!(out, vec!["Auth changed (src/auth/jwt.go and 1 more)"]);
+  }
+
+  #[test]
+  fn excluded_files_do_not_drive_score_or_hotspots() {
+    let mut input = make_input(0, 5000, 0);
+    input.files_changed = vec!["package-lock.json".to_string(), "src/a.ts".to_string()];
+    let diffs = vec![
+      file_diff("package-lock.json", 4990, 0),
+      file_diff("src/a.ts", 10, 0),
+    ];
+    let excluded = vec![ExcludedFile {
+      path: "package-lock.json".to_string(),
+      reason: "lockfile".to_string(),
+    }];
+    let with = compute_impact_score(&input, &mut [], &diffs, &excluded);
+    let without = compute_impact_score(&input, &mut [], &diffs, &[]);
+    assert_eq!(with, 2 + 1);
+    assert_eq!(without, 4 + 40);
+    let hot = compute_hotspots(&input.files_changed, &diffs, &excluded, &[], &[], 10);
+    assert_eq!(paths(&hot), vec!["src/a.ts"]);
   }
 }
@@ -0,0 +1,316 @@
+//! Impact scorers: turn change features and flag hits into the 0–100 score.
+//!
+//! `WeightedScorer` (the default) adds each flag hit's rule weight to a
+//! file and churn base. `LegacyScorer` is the original fixed formula, kept
+//! so stored `legacy` v1 scores stay comparable. `LogisticScorer` maps the
+//! same inputs through a logistic model whose coefficients are fitted
+//! offline against incident history and loaded from a file:
+//! RISK_ENGINE_MODEL=/path/to/model.{json,toml}, or a `risk-model.json` /
+//! `risk-model.toml` next to the binary.
+
+use crate::error::EngineError;
+use crate::flags::RiskFlag;
+use crate::types::FlagHit;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// What a change looks like before any flag: counts that exclude
+/// generated/vendored files and lockfiles, plus the raw counts the legacy
+/// formula used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScoreFeatures {
+  /// Changed files that count toward the score.
+  pub files: u32,
+  /// Added + removed lines in those files.
+  pub churn: u32,
+  /// Every listed file, excluded or not.
+  pub listed_files: u32,
+  /// Reported additions + deletions.
+  pub reported_churn: u32,
+}
+
+/// Produces the impact score. Records on each hit the points it added, so
+/// callers can explain the number.
+pub trait Scorer: std::fmt::Debug + Send + Sync {
+  /// Reported in the output as `scorer`.
+  fn name(&self) -> &str;
+  /// Reported in the output as `model_version`.
+  fn version(&self) -> &str;
+  /// Score 0–100; sets `contribution` on every hit.
+  fn score(&self, features: &ScoreFeatures, flag_hits: &mut [FlagHit]) -> u8;
+}
+
+/// `min(files, 30) × 2 + min(churn / 10, 40)` plus each flag's rule
+/// weight, capped at 100.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedScorer;
+
+impl Scorer for WeightedScorer {
+  fn name(&self) -> &str {
+    "weighted"
+  }
+
+  fn version(&self) -> &str {
+    "1"
+  }
+
+  fn score(&self, features: &ScoreFeatures, flag_hits: &mut [FlagHit]) -> u8 {
+    additive(base(features.files, features.churn), flag_hits, |hit| {
+      hit.weight
+    })
+  }
+}
+
+/// The original formula: the same base over every listed file and the
+/// reported churn, +15 for auth / secrets / payment, +10 for migration /
+/// config, +5 for deps. Other flags add nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LegacyScorer;
+
+impl Scorer for LegacyScorer {
+  fn name(&self) -> &str {
+    "legacy"
+  }
+
+  fn version(&self) -> &str {
+    "1"
+  }
+
+  fn score(&self, features: &ScoreFeatures, flag_hits: &mut [FlagHit]) -> u8 {
+    let base = base(features.listed_files, features.reported_churn);
+    additive(base, flag_hits, |hit| match hit.flag {
+      RiskFlag::Auth | RiskFlag::Secrets | RiskFlag::Payment => 15,
+      RiskFlag::Migration | RiskFlag::Config => 10,
+      RiskFlag::Deps => 5,
+      _ => 0,
+    })
+  }
+}
+
+fn base(files: u32, churn: u32) -> i32 {
+  (files.min(30) * 2 + (churn / 10).min(40)) as i32
+}
+
+/// `base` plus each hit's points, capped at 0–100; records contributions.
+fn additive(base: i32, flag_hits: &mut [FlagHit], points: impl Fn(&FlagHit) -> i32) -> u8 {
+  let mut score = base;
+  for hit in flag_hits.iter_mut() {
+    let before = score.clamp(0, 100);
+    score += points(hit);
+    hit.contribution = score.clamp(0, 100) - before;
+  }
+  score.clamp(0, 100) as u8
+}
+
+/// Coefficients of a logistic model, as written by the calibration tool.
+/// `score = round(100 × σ(intercept + files × ln(1 + files)
+/// + churn × ln(1 + churn) + Σ flag coefficients))`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogisticModel {
+  /// Free-form model version, e.g. the fit date.
+  pub version: String,
+  #[serde(default)]
+  pub intercept: f64,
+  /// Coefficient on `ln(1 + files)`.
+  #[serde(default)]
+  pub files: f64,
+  /// Coefficient on `ln(1 + churn)`.
+  #[serde(default)]
+  pub churn: f64,
+  /// Coefficient per risk flag.
+  #[serde(default)]
+  pub flags: BTreeMap<String, f64>,
+  /// Coefficient for risk flags missing from `flags`.
+  #[serde(default)]
+  pub default_flag: f64,
+}
+
+impl LogisticModel {
+  /// Log-odds before flags.
+  pub fn base_logit(&self, features: &ScoreFeatures) -> f64 {
+    self.intercept
+      + self.files * (features.files as f64).ln_1p()
+      + self.churn * (features.churn as f64).ln_1p()
+  }
+
+  /// Coefficient for a hit; discounts (negative weight) missing from
+  /// `flags` get 0 rather than `default_flag`.
+  pub fn flag_coefficient(&self, hit: &FlagHit) -> f64 {
+    match self.flags.get(hit.flag.as_str()) {
+      Some(c) => *c,
+      None if hit.weight < 0 => 0.0,
+      None => self.default_flag,
+    }
+  }
+
+  /// Load from a `.json` or `.toml` file.
+  pub fn load(path: &Path) -> Result<Self, EngineError> {
+    let origin = path.display().to_string();
+    let raw =
+      std::fs::read_to_string(path).map_err(|e| EngineError::model(&origin, &e.to_string()))?;
+    let is_json = path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let model: Self = if is_json {
+      serde_json::from_str(&raw).map_err(|e| EngineError::model(&origin, &e.to_string()))?
+    } else {
+      toml::from_str(&raw).map_err(|e| EngineError::model(&origin, &e.to_string()))?
+    };
+    if model.version.is_empty() {
+      return Err(EngineError::model(&origin, "model has no version"));
+    }
+    Ok(model)
+  }
+}
+
+/// Logistic model: the score is the modelled incident probability in
+/// percent, so large changes approach 100 instead of saturating at it.
+#[derive(Debug, Clone)]
+pub struct LogisticScorer {
+  model: LogisticModel,
+}
+
+impl LogisticScorer {
+  pub fn new(model: LogisticModel) -> Self {
+    Self { model }
+  }
+}
+
+impl Scorer for LogisticScorer {
+  fn name(&self) -> &str {
+    "logistic"
+  }
+
+  fn version(&self) -> &str {
+    &self.model.version
+  }
+
+  fn score(&self, features: &ScoreFeatures, flag_hits: &mut [FlagHit]) -> u8 {
+    let mut logit = self.model.base_logit(features);
+    let mut score = percent(logit);
+    for hit in flag_hits.iter_mut() {
+      logit += self.model.flag_coefficient(hit);
+      let next = percent(logit);
+      hit.contribution = next as i32 - score as i32;
+      score = next;
+    }
+    score
+  }
+}
+
+fn percent(logit: f64) -> u8 {
+  let p = 1.0 / (1.0 + (-logit).exp());
+  (p * 100.0).round().clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::flags::RiskFlag;
+
+  fn hit(flag: &str, weight: i32) -> FlagHit {
+    FlagHit::new(RiskFlag::from_name(flag), flag, weight, "")
+  }
+
+  fn model() -> LogisticModel {
+    LogisticModel {
+      version: "2026-10-01".to_string(),
+      intercept: -4.0,
+      files: 0.5,
+      churn: 0.4,
+      flags: [("auth".to_string(), 1.5)].into_iter().collect(),
+      default_flag: 0.5,
+    }
+  }
+
+  #[test]
+  fn weighted_adds_every_rule_weight() {
+    let mut hits = [hit("auth", 15), hit("secret_leak", 40)];
+    let features = ScoreFeatures {
+      files: 3,
+      churn: 120,
+      ..ScoreFeatures::default()
+    };
+    assert_eq!(WeightedScorer.score(&features, &mut hits), 6 + 12 + 15 + 40);
+    assert_eq!((hits[0].contribution, hits[1].contribution), (15, 40));
+    assert_eq!(
+      (WeightedScorer.name(), WeightedScorer.version()),
+      ("weighted", "1")
+    );
+  }
+
+  #[test]
+  fn legacy_matches_original_formula() {
+    // Original bumps regardless of rule weight; newer flags add nothing.
+    let mut hits = [hit("auth", 20), hit("deps", 15), hit("secret_leak", 40)];
+    let features = ScoreFeatures {
+      files: 2,
+      churn: 100,
+      listed_files: 3,
+      reported_churn: 120,
+    };
+    assert_eq!(LegacyScorer.score(&features, &mut hits), 6 + 12 + 15 + 5);
+    let contributions: Vec<i32> = hits.iter().map(|h| h.contribution).collect();
+    assert_eq!(contributions, vec![15, 5, 0]);
+    assert_eq!(
+      (LegacyScorer.name(), LegacyScorer.version()),
+      ("legacy", "1")
+    );
+  }
+
+  #[test]
+  fn logistic_does_not_saturate_on_large_changes() {
+    let scorer = LogisticScorer::new(model());
+    let big = ScoreFeatures {
+      files: 200,
+      churn: 20_000,
+      ..ScoreFeatures::default()
+    };
+    let bigger = ScoreFeatures {
+      files: 400,
+      churn: 80_000,
+      ..ScoreFeatures::default()
+    };
+    let (a, b) = (scorer.score(&big, &mut []), scorer.score(&bigger, &mut []));
+    assert!(a < b && b < 100, "{} {}", a, b);
+    assert_eq!(WeightedScorer.score(&big, &mut []), 100);
+  }
+
+  #[test]
+  fn logistic_contributions_use_flag_coefficients() {
+    let scorer = LogisticScorer::new(model());
+    let features = ScoreFeatures {
+      files: 2,
+      churn: 30,
+      ..ScoreFeatures::default()
+    };
+    let base = scorer.score(&features, &mut []);
+    let mut hits = [hit("auth", 15), hit("deps", 5)];
+    let score = scorer.score(&features, &mut hits);
+    assert!(hits[0].contribution > hits[1].contribution);
+    assert_eq!(
+      score as i32,
+      base as i32 + hits[0].contribution + hits[1].contribution
+    );
+  }
+
+  #[test]
+  fn model_file_needs_a_version() {
+    let dir = std::env::temp_dir().join(format!("risk-model-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let good = dir.join("model.toml");
+    std::fs::write(
+      &good,
+      "version = \"v3\"\nintercept = -3.0\nchurn = 0.2\n[flags]\nauth = 1.1\n",
+    )
+    .unwrap();
+    let model = LogisticModel::load(&good).unwrap();
+    assert_eq!(model.flag_coefficient(&hit("auth", 15)), 1.1);
+    let bad = dir.join("model.json");
+    std::fs::write(&bad, r#"{"intercept": -3.0}"#).unwrap();
+    let err = LogisticModel::load(&bad).unwrap_err();
+    assert!(err.to_string().contains("model"));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,329 @@
+//! Source ↔ test pairing: did the push update the tests for the logic it
+//! changed?
+//!
+//! A test file pairs with a source file of the same language family when
+//! their names match once test affixes are stripped (`foo.test.ts`,
+//! `foo.spec.ts`, `test_foo.py`, `foo_spec.rb`, `FooTest.java` → `foo`). Go
+//! tests must also sit in the same directory (`pkg/x_test.go` ↔ `pkg/x.go`).
+//! Rust files count as their own test when the diff touches an inline
+//! `#[cfg(test)]` module or a `#[test]` function.
+
+use crate::classify::{FileClass, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::findings::name_list;
+use crate::flags::RiskFlag;
+use crate::types::{ExcludedFile, FlagHit, TestCoverage};
+
+/// `untested_change` weight: logic changed with no matching test change.
+const UNTESTED_WEIGHT: i32 = 10;
+/// `tests_updated` weight (a discount): every logic change has a matching
+/// test change.
+const TESTED_DISCOUNT: i32 = -10;
+
+/// Languages whose sources and tests pair with each other.
+fn family(language: &str) -> &str {
+  match language {
+    "typescript" | "javascript" => "js",
+    other => other,
+  }
+}
+
+/// (directory, file name without its last extension).
+fn split(path: &str) -> (&str, &str) {
+  let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+  let stem = match name.rsplit_once('.') {
+    Some((stem, _)) if !stem.is_empty() => stem,
+    _ => name,
+  };
+  (dir, stem)
+}
+
+/// Pairing key of a test file: its stem without test affixes, lowercased.
+fn test_key(stem: &str) -> String {
+  let mut key = stem;
+  for suffix in [".test", ".spec", "_test", "_spec", ".e2e", "Tests", "Test"] {
+    if let Some(rest) = key.strip_suffix(suffix) {
+      key = rest;
+      break;
+    }
+  }
+  key = key.strip_prefix("test_").unwrap_or(key);
+  key.to_lowercase()
+}
+
+/// Whether the diff edits logic and / or inline tests of a source file.
+fn edits(diff: &FileDiff, language: &str) -> (bool, bool) {
+  let (mut logic, mut tests) = (false, false);
+  for hunk in &diff.hunks {
+    // Brace depth within the test module or function, once its body opened;
+    // a hunk headed by `mod tests` starts inside the module body.
+    let mut in_tests = language == "rust"
+      && (hunk.section.contains("mod tests") || hunk.section.contains("#[cfg(test)]"));
+    let mut depth = i32::from(in_tests);
+    let mut opened = in_tests;
+    for line in &hunk.lines {
+      let text = line.text.trim();
+      if language == "rust"
+        && !in_tests
+        && (text.starts_with("#[cfg(test)]") || text.starts_with("#[test]"))
+      {
+        (in_tests, depth, opened) = (true, 0, false);
+      }
+      let test_line = in_tests;
+      if in_tests && line.kind != LineKind::Removed {
+        for c in text.chars() {
+          match c {
+            '{' => (depth, opened) = (depth + 1, true),
+            '}' => depth -= 1,
+            _ => {}
+          }
+        }
+        // The closing brace is the last test line.
+        in_tests = !opened || depth > 0;
+      }
+      if line.kind == LineKind::Context || text.is_empty() {
+        continue;
+      }
+      if test_line {
+        tests = true;
+      } else {
+        logic = true;
+      }
+    }
+  }
+  (logic, tests)
+}
+
+/// One entry per source file whose logic changed, with the changed tests
+/// that pair with it (empty when untested). Files without a diff are
+/// skipped: a file list alone can't tell logic edits from comment or test
+/// edits, and the webhook doesn't send diff text.
+pub fn pair(
+  classes: &[FileClass],
+  diffs: &[FileDiff],
+  excluded: &[ExcludedFile],
+) -> Vec<TestCoverage> {
+  let counted = |c: &&FileClass| !excluded.iter().any(|e| e.path == c.path);
+  // (path, directory, key, language family) per changed test file.
+  let tests: Vec<(&str, &str, String, &str)> = classes
+    .iter()
+    .filter(counted)
+    .filter(|c| c.role == FileRole::Test)
+    .filter_map(|c| {
+      let language = c.language.as_deref()?;
+      let (dir, stem) = split(&c.path);
+      Some((c.path.as_str(), dir, test_key(stem), family(language)))
+    })
+    .collect();
+  let mut out = Vec::new();
+  for class in classes.iter().filter(counted) {
+    if class.role != FileRole::Source {
+      continue;
+    }
+    let Some(language) = class.language.as_deref() else {
+      continue;
+    };
+    let Some(diff) = diffs.iter().find(|d| d.path == class.path) else {
+      continue;
+    };
+    if diff.binary {
+      continue;
+    }
+    let (logic, inline_tests) = edits(diff, language);
+    if !logic {
+      continue;
+    }
+    let (dir, stem) = split(&class.path);
+    let key = stem.to_lowercase();
+    let mut paired: Vec<String> = tests
+      .iter()
+      .filter(|(_, test_dir, test_key, test_family)| {
+        *test_key == key
+          && *test_family == family(language)
+          && (language != "go" || *test_dir == dir)
+      })
+      .map(|(path, ..)| path.to_string())
+      .collect();
+    if inline_tests {
+      paired.insert(0, class.path.clone());
+    }
+    out.push(TestCoverage {
+      path: class.path.clone(),
+      tests: paired,
+    });
+  }
+  out
+}
+
+/// `untested_change` when some logic changed without its tests, otherwise
+/// the `tests_updated` discount when every logic change came with tests.
+pub fn coverage_hit(coverage: &[TestCoverage]) -> Option<FlagHit> {
+  if coverage.is_empty() {
+    return None;
+  }
+  let untested: Vec<&str> = coverage
+    .iter()
+    .filter(|c| c.tests.is_empty())
+    .map(|c| c.path.as_str())
+    .collect();
+  if untested.is_empty() {
+    let mut hit = FlagHit::new(
+      RiskFlag::TestsUpdated,
+      "tests-updated",
+      TESTED_DISCOUNT,
+      "Matching tests updated for every changed source file",
+    );
+    hit.paths = coverage.iter().map(|c| c.path.clone()).collect();
+    return Some(hit);
+  }
+  let mut hit = FlagHit::new(
+    RiskFlag::UntestedChange,
+    "source-without-tests",
+    UNTESTED_WEIGHT,
+    &format!(
+      "Logic changed without matching test changes: {}",
+      name_list(&untested)
+    ),
+  );
+  hit.paths = untested.iter().map(|p| p.to_string()).collect();
+  Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify_all;
+  use crate::diff::parse_unified_diff;
+
+  fn coverage(files: &[&str], diff: &str) -> Vec<(String, Vec<String>)> {
+    let paths: Vec<String> = files.iter().map(|f| f.to_string()).collect();
+    pair(&classify_all(&paths), &parse_unified_diff(diff), &[])
+      .into_iter()
+      .map(|c| (c.path, c.tests))
+      .collect()
+  }
+
+  fn entry(path: &str, tests: &[&str]) -> (String, Vec<String>) {
+    (
+      path.to_string(),
+      tests.iter().map(|t| t.to_string()).collect(),
+    )
+  }
+
+  #[test]
+  fn pairs_by_conventional_test_names() {
+    let files = [
+      "src/users.ts",
+      "src/__tests__/users.test.ts",
+      "pkg/store/db.go",
+      "pkg/other/db_test.go",
+      "app/billing.py",
+      "tests/test_billing.py",
+      "README.md",
+    ];
+    let diff: String = files
+      .iter()
+      .map(|f| format!("--- a/{f}\n+++ b/{f}\n@@ -1 +1 @@\n-a\n+b\n"))
+      .collect();
+    assert_eq!(
+      coverage(&files, &diff),
+      vec![
+        entry("src/users.ts", &["src/__tests__/users.test.ts"]),
+        entry("pkg/store/db.go", &[]),
+        entry("app/billing.py", &["tests/test_billing.py"]),
+      ]
+    );
+  }
+
+  #[test]
+  fn file_list_without_a_diff_pairs_nothing() {
+    let files = ["src/users.ts", "pkg/store/db.go", "src/score.rs"];
+    assert!(coverage(&files, "").is_empty());
+  }
+
+  #[test]
+  fn rust_inline_tests_count_for_their_file() {
+    let diff = "\
+--- a/src/score.rs
++++ b/src/score.rs
+@@ -10,2 +10,2 @@ pub fn compute() {
+-  let x = 1;
++  let x = 2;
+@@ -90,2 +90,3 @@ mod tests {
+   #[test]
+   fn compute_works() {
++    assert_eq!(compute(), 2);
+--- a/src/diff.rs
++++ b/src/diff.rs
+@@ -50,0 +50,1 @@ mod tests {
++    assert!(true);
+";
+    assert_eq!(
+      coverage(&["src/score.rs", "src/diff.rs"], diff),
+      vec![entry("src/score.rs", &["src/score.rs"])]
+    );
+  }
+
+  #[test]
+  fn rust_source_after_the_tests_module_is_logic() {
+    let diff = "\
+--- a/src/score.rs
++++ b/src/score.rs
+@@ -90,5 +90,6 @@ mod tests {
+   #[test]
+   fn compute_works() {
++    assert_eq!(compute(), 2);
+   }
+ }
+-pub fn extra() -> u8 { 1 }
++pub fn extra() -> u8 { 2 }
+--- a/src/diff.rs
++++ b/src/diff.rs
+@@ -40,4 +40,5 @@ pub fn parse() {
+ #[cfg(test)]
+ mod tests {
++  #[test]
++  fn parses() {}
+ }
+-fn helper() {}
+";
+    assert_eq!(
+      coverage(&["src/score.rs", "src/diff.rs"], diff),
+      vec![
+        entry("src/score.rs", &["src/score.rs"]),
+        entry("src/diff.rs", &["src/diff.rs"]),
+      ]
+    );
+  }
+
+  #[test]
+  fn hit_flags_untested_logic_or_discounts_full_coverage() {
+    let untested = coverage_hit(&[
+      TestCoverage {
+        path: "a.ts".to_string(),
+        tests: vec!["a.test.ts".to_string()],
+      },
+      TestCoverage {
+        path: "b.go".to_string(),
+        tests: Vec::new(),
+      },
+    ])
+    .unwrap();
+    assert_eq!(
+      (untested.flag.as_str(), untested.weight),
+      ("untested_change", UNTESTED_WEIGHT)
+    );
+    assert_eq!(untested.paths, vec!["b.go"]);
+
+    let tested = coverage_hit(&[TestCoverage {
+      path: "a.ts".to_string(),
+      tests: vec!["a.test.ts".to_string()],
+    }])
+    .unwrap();
+    assert_eq!(
+      (tested.flag.as_str(), tested.weight),
+      ("tests_updated", TESTED_DISCOUNT)
+    );
+    assert!(coverage_hit(&[]).is_none());
+  }
+}
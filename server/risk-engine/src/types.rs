@@ -1,25 +1,436 @@
 //! Input/output types for the risk engine (JSON contract with Node).
 
+use crate::classify::FileClass;
+use crate::flags::{ChangeType, RiskFlag, Severity};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Version of the Output contract, reported as `schema_version`. Bumped when
+/// a field is removed, renamed or changes meaning; new fields don't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
 /// Input: one JSON object from Node (matches webhook pushData).
-#[derive(Debug, Deserialize)]
+/// Top-level fields describe the whole push; `commits` optionally breaks it
+/// down so each commit is also scored on its own.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct Input {
   pub commit_message: String,
   pub files_changed: Vec<String>,
   pub additions: u32,
   pub deletions: u32,
+  /// Optional per-file status (GitHub's compare / pull request files); a
+  /// `previous_path` marks a rename or move.
+  #[serde(default)]
+  pub file_changes: Vec<FileChange>,
+  /// Optional unified diff; parsed for per-file churn.
+  #[serde(default)]
+  pub diff_text: Option<String>,
+  /// Optional `.gitattributes` contents; `linguist-generated` /
+  /// `linguist-vendored` paths are excluded from impact scoring.
+  #[serde(default)]
+  pub gitattributes: Option<String>,
+  /// Optional CODEOWNERS contents; maps changed paths to owners.
+  #[serde(default)]
+  pub codeowners: Option<String>,
+  /// Optional per-file history snapshot (from PushLog's DB); weights
+  /// hotspots and raises `incident_history`.
+  #[serde(default)]
+  pub history: Vec<FileHistory>,
+  /// Optional per-commit breakdown of a multi-commit push.
+  #[serde(default)]
+  pub commits: Vec<CommitInput>,
+}
+
+/// Status of one changed file, as reported by GitHub.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct FileChange {
+  pub path: String,
+  /// "added", "removed", "modified", "renamed", "copied", ...
+  #[serde(default)]
+  pub status: Option<String>,
+  /// Path before a rename or move.
+  #[serde(default)]
+  pub previous_path: Option<String>,
+  #[serde(default)]
+  pub additions: Option<u32>,
+  #[serde(default)]
+  pub deletions: Option<u32>,
+}
+
+/// Recent history of one file.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct FileHistory {
+  pub path: String,
+  /// Commits touching the file in the history window.
+  #[serde(default)]
+  pub change_count: u32,
+  /// Incidents attributed to changes in the file.
+  #[serde(default)]
+  pub incident_count: u32,
+  /// Distinct authors of those commits.
+  #[serde(default)]
+  pub author_count: u32,
+}
+
+/// One commit of a multi-commit push.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct CommitInput {
+  #[serde(default)]
+  pub id: String,
+  pub message: String,
+  #[serde(default)]
+  pub files: Vec<String>,
+  #[serde(default)]
+  pub additions: u32,
+  #[serde(default)]
+  pub deletions: u32,
   #[serde(default)]
-  #[allow(dead_code)] // reserved for future diff-based rules
   pub diff_text: Option<String>,
 }
 
 /// Output: one JSON object to stdout for Node to parse.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Output {
+  /// Output contract version (`SCHEMA_VERSION`).
+  pub schema_version: u32,
+  /// For multi-commit input, at least the riskiest commit's score.
   pub impact_score: u8,
-  pub risk_flags: Vec<String>,
-  pub change_type_tags: Vec<String>,
+  /// Scorer that produced `impact_score` ("weighted", "legacy" or
+  /// "logistic").
+  pub scorer: String,
+  /// Version of that scorer's model.
+  pub model_version: String,
+  pub risk_flags: Vec<RiskFlag>,
+  pub change_type_tags: Vec<ChangeType>,
+  /// SHAs named by "This reverts commit <sha>" (every commit of the push).
+  pub reverted_commits: Vec<String>,
   pub hotspot_files: Vec<String>,
+  /// `hotspot_files` with the numbers behind their rank.
+  pub hotspots: Vec<Hotspot>,
   pub explanations: Vec<String>,
+  /// Conventional Commit type ("feat", "fix", "perf", ...); null if not conventional.
+  pub commit_type: Option<String>,
+  /// Conventional Commit scope, e.g. "api" in `feat(api): ...`.
+  pub commit_scope: Option<String>,
+  /// `!` marker or `BREAKING CHANGE:` footer.
+  pub breaking_change: bool,
+  /// Why the score is what it is: one entry per risk flag, same order as `risk_flags`.
+  pub flag_hits: Vec<FlagHit>,
+  /// Every CODEOWNERS owner of a changed path (empty without `codeowners`).
+  pub owners: Vec<String>,
+  /// Owners per changed path; an empty list means unowned.
+  pub file_owners: Vec<FileOwners>,
+  /// Language, role and ecosystem of each path in `files_changed`.
+  pub file_classes: Vec<FileClass>,
+  /// Files left out of the impact score (generated, vendored, lockfiles).
+  pub excluded_files: Vec<ExcludedFile>,
+  /// Files renamed or moved, from `file_changes` and diff rename headers.
+  pub renamed_files: Vec<RenamedFile>,
+  /// Credential-looking literals in added lines (redacted), backing `secret_leak`.
+  pub secret_findings: Vec<SecretFinding>,
+  /// Packages added, removed or re-versioned in manifest diffs.
+  pub dependency_changes: Vec<DependencyChange>,
+  /// Destructive or locking statements in SQL / migration diffs.
+  pub migration_findings: Vec<MigrationFinding>,
+  /// Risky trigger and token-permission edits in GitHub Actions workflows.
+  pub workflow_findings: Vec<WorkflowFinding>,
+  /// Exported / public declarations added, removed or re-signed.
+  pub api_changes: Vec<ApiChange>,
+  /// HTTP routes registered, unregistered or whose handler changed; `route`
+  /// joins with incidents' `api_route`.
+  pub routes: Vec<RouteChange>,
+  /// Per changed source file, the changed tests that pair with it.
+  pub test_coverage: Vec<TestCoverage>,
+  /// Per-commit scores (empty unless the input had `commits`).
+  pub commits: Vec<CommitScore>,
+  /// Push-level summary of `commits`; null for single-commit input.
+  pub push_rollup: Option<PushRollup>,
+}
+
+/// A hotspot: this push's churn times the file's historical instability.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Hotspot {
+  pub path: String,
+  /// Added + removed lines in this diff (0 without diff text).
+  pub churn: u32,
+  pub change_count: u32,
+  pub incident_count: u32,
+  pub author_count: u32,
+  /// `(1 + churn) × instability`, rounded to 2 decimals.
+  pub score: f64,
+}
+
+/// CODEOWNERS owners of one changed path.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileOwners {
+  pub path: String,
+  pub owners: Vec<String>,
+}
+
+/// A file excluded from impact scoring and why: "generated_path",
+/// "vendored_path", "lockfile", "gitattributes_generated",
+/// "gitattributes_vendored" or "generated_header".
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ExcludedFile {
+  pub path: String,
+  pub reason: String,
+}
+
+/// Score of one commit within a push.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CommitScore {
+  pub id: String,
+  pub impact_score: u8,
+  pub risk_flags: Vec<RiskFlag>,
+  pub change_type_tags: Vec<ChangeType>,
+  pub reverted_commits: Vec<String>,
+  pub commit_type: Option<String>,
+  pub breaking_change: bool,
+}
+
+/// Push-level rollup of per-commit scores.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PushRollup {
+  pub commit_count: usize,
+  pub max_impact_score: u8,
+  pub mean_impact_score: u8,
+  /// Id of the highest-scoring commit (earliest on ties).
+  pub riskiest_commit: Option<String>,
+  /// Ids of commits scoring at or above the risky threshold (50).
+  pub risky_commits: Vec<String>,
+}
+
+/// One suspected secret in an added diff line. The secret itself is redacted.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SecretFinding {
+  pub path: String,
+  pub line: u32,
+  /// e.g. "aws_access_key", "github_token", "private_key", "high_entropy_string".
+  pub kind: String,
+  /// Trimmed line with the secret masked.
+  pub snippet: String,
+}
+
+/// One package change in a manifest diff (package.json, Cargo.toml, go.mod,
+/// requirements*.txt).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DependencyChange {
+  /// Manifest path.
+  pub path: String,
+  /// "npm", "cargo", "go" or "pip".
+  pub ecosystem: String,
+  pub name: String,
+  pub change: DependencyChangeKind,
+  /// Old version spec (null for added or unversioned entries).
+  pub from: Option<String>,
+  /// New version spec (null for removed or unversioned entries).
+  pub to: Option<String>,
+  /// False for go.mod `// indirect` requirements.
+  pub direct: bool,
+}
+
+/// How a dependency's version moved; `changed` when either side isn't a
+/// plain semver version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyChangeKind {
+  Added,
+  Removed,
+  Major,
+  Minor,
+  Patch,
+  Downgrade,
+  Changed,
+}
+
+/// A renamed or moved file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct RenamedFile {
+  pub path: String,
+  pub previous_path: String,
+  /// Lines edited along with the move.
+  pub churn: u32,
+  /// Moved without edits: no file count, never a hotspot.
+  pub pure: bool,
+}
+
+/// One unsafe statement found by the migration linter.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MigrationFinding {
+  pub path: String,
+  /// New-file line where the statement starts.
+  pub line: u32,
+  /// e.g. "drop_table", "drop_column", "alter_column_type",
+  /// "not_null_without_default", "create_index_without_concurrently".
+  pub rule: String,
+  /// High or medium.
+  pub severity: Severity,
+  pub message: String,
+  /// The statement, truncated.
+  pub snippet: String,
+}
+
+/// One risky edit found in a GitHub Actions workflow (backs `ci`).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WorkflowFinding {
+  pub path: String,
+  /// New-file line (old-file line for "permissions_removed").
+  pub line: u32,
+  /// "pull_request_target", "permissions_write_all", "permission_write" or
+  /// "permissions_removed".
+  pub rule: String,
+  /// High or medium.
+  pub severity: Severity,
+  pub message: String,
+  /// The offending line, truncated.
+  pub snippet: String,
+}
+
+/// One public API declaration change (backs `api_surface`).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ApiChange {
+  pub path: String,
+  pub symbol: String,
+  pub kind: ApiKind,
+  pub change: ApiChangeKind,
+  /// New-file line (old-file line for removals).
+  pub line: u32,
+}
+
+/// What kind of public symbol an [`ApiChange`] names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKind {
+  Function,
+  Class,
+  Type,
+  Const,
+  /// HTTP route; the symbol is "METHOD /path".
+  Route,
+}
+
+/// How a public symbol changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+  Added,
+  Removed,
+  /// The signature differs.
+  Changed,
+}
+
+/// An HTTP route touched by the diff.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RouteChange {
+  /// File the route is registered in.
+  pub path: String,
+  /// Upper-case HTTP method, or "ANY" / "ALL" when not method-specific.
+  pub method: String,
+  /// Route path as registered, e.g. "/api/users/:id".
+  pub route: String,
+  pub change: RouteChangeKind,
+  /// Registration line (old-file line for removals).
+  pub line: u32,
+}
+
+/// How a route registration was touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteChangeKind {
+  Added,
+  Removed,
+  /// The registration line was edited.
+  Changed,
+  /// The handler body was edited.
+  Modified,
+}
+
+/// A source file whose logic changed, and the changed tests that cover it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TestCoverage {
+  pub path: String,
+  /// Paired test files changed in the same push; the file itself for Rust
+  /// inline tests. Empty when untested.
+  pub tests: Vec<String>,
+}
+
+/// A risk flag raised by a rule, with the paths that triggered it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FlagHit {
+  pub flag: RiskFlag,
+  /// The flag's own severity (see `RiskFlag::severity`).
+  pub severity: Severity,
+  /// Id of the rule that set the weight (highest-weight match).
+  pub rule: String,
+  /// Nominal weight of the rule.
+  pub weight: i32,
+  /// Points this flag actually added to `impact_score` (less than `weight`
+  /// when the score hit the 100 cap).
+  pub contribution: i32,
+  pub explanation: String,
+  pub paths: Vec<String>,
+}
+
+impl FlagHit {
+  /// An empty `explanation` falls back to the flag's own.
+  pub fn new(flag: RiskFlag, rule: &str, weight: i32, explanation: &str) -> Self {
+    let explanation = match explanation {
+      "" => flag.explanation(),
+      text => text,
+    };
+    Self {
+      severity: flag.severity(),
+      flag,
+      rule: rule.to_string(),
+      weight,
+      contribution: 0,
+      explanation: explanation.to_string(),
+      paths: Vec::new(),
+    }
+  }
+}
+
+// ---------------------------------------------------------------------------
+// CLI stream wrappers
+// ---------------------------------------------------------------------------
+
+/// One `--stream` result line: the output plus the caller's correlation id.
+#[derive(Debug, Serialize)]
+pub struct StreamOutput {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub id: Option<serde_json::Value>,
+  #[serde(flatten)]
+  pub output: Output,
+}
+
+/// Structured error output for invalid input lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorOutput {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub id: Option<serde_json::Value>,
+  pub error: bool,
+  pub message: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub field: Option<String>,
+}
+
+impl ErrorOutput {
+  pub fn new(message: impl Into<String>) -> Self {
+    Self {
+      id: None,
+      error: true,
+      message: message.into(),
+      field: None,
+    }
+  }
+
+  pub fn with_id(mut self, id: Option<serde_json::Value>) -> Self {
+    self.id = id;
+    self
+  }
+
+  pub fn with_field(mut self, field: impl Into<String>) -> Self {
+    self.field = Some(field.into());
+    self
+  }
 }
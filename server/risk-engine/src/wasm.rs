@@ -0,0 +1,52 @@
+//! WebAssembly bindings (feature `wasm`, target `wasm32-unknown-unknown`),
+//! so the client can preview a branch's risk with the same scoring.
+//!
+//! Uses the built-in rules and the weighted scorer unless a model is passed;
+//! there is no environment or filesystem to load them from. Errors are
+//! thrown as the binary's error object (`{error, message, field?}`).
+
+use crate::types::ErrorOutput;
+use crate::{try_run, Config, EngineError, Input, LogisticModel, LogisticScorer, SCHEMA_VERSION};
+use serde::Serialize;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+/// Score one push. `input` is the same object the binary reads on stdin;
+/// `model` is an optional logistic model (as written by risk-calibrate).
+#[wasm_bindgen(js_name = scorePush)]
+pub fn score_push(input: JsValue, model: JsValue) -> Result<JsValue, JsValue> {
+  let input: Input = serde_wasm_bindgen::from_value(input)
+    .map_err(|e| throw(ErrorOutput::new(format!("json parse: {}", e))))?;
+  let mut config = Config::default();
+  if !model.is_undefined() && !model.is_null() {
+    let model: LogisticModel = serde_wasm_bindgen::from_value(model)
+      .map_err(|e| throw(ErrorOutput::new(format!("model: {}", e)).with_field("model")))?;
+    config.scorer = Arc::new(LogisticScorer::new(model));
+  }
+  let out = try_run(&input, &config).map_err(|e| match e {
+    EngineError::Validation { field, reason } => throw(ErrorOutput::new(reason).with_field(field)),
+    other => throw(ErrorOutput::new(other.to_string())),
+  })?;
+  to_js(&out)
+}
+
+/// Output contract version `scorePush` results follow.
+#[wasm_bindgen(js_name = schemaVersion)]
+pub fn schema_version() -> u32 {
+  SCHEMA_VERSION
+}
+
+/// Plain JS objects (not Maps), with `null` for missing optionals, to match
+/// the binary's JSON.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+  value
+    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    .map_err(|e| throw(ErrorOutput::new(format!("serialize: {}", e))))
+}
+
+/// The error object, or just its message if even that fails to convert.
+fn throw(err: ErrorOutput) -> JsValue {
+  err
+    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    .unwrap_or_else(|_| JsValue::from_str(&err.message))
+}
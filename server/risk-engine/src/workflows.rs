@@ -0,0 +1,216 @@
+//! Risky edits to GitHub Actions workflows: triggers that run fork code with
+//! a privileged token, and broadened `GITHUB_TOKEN` permissions.
+//!
+//! Only added lines are checked, except for a removed `permissions:` key,
+//! which widens the token back to the repository default.
+
+use crate::classify::{classify, FileClass, FileRole};
+use crate::diff::{FileDiff, LineKind};
+use crate::findings::{snippet, worst_finding_hit};
+use crate::flags::{RiskFlag, Severity};
+use crate::types::{FlagHit, WorkflowFinding};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Scopes of the `permissions:` block that accept `write`.
+const SCOPES: [&str; 14] = [
+  "actions",
+  "attestations",
+  "checks",
+  "contents",
+  "deployments",
+  "discussions",
+  "id-token",
+  "issues",
+  "packages",
+  "pages",
+  "pull-requests",
+  "repository-projects",
+  "security-events",
+  "statuses",
+];
+
+fn pull_request_target() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r"\bpull_request_target\b").expect("valid workflow pattern"))
+}
+
+fn write_all() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r#"^\s*permissions\s*:\s*["']?write-all\b"#).expect("valid workflow pattern")
+  })
+}
+
+fn scope_write() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| {
+    Regex::new(r#"^\s*([a-z-]+)\s*:\s*["']?write\b"#).expect("valid workflow pattern")
+  })
+}
+
+fn permissions_key() -> &'static Regex {
+  static RE: OnceLock<Regex> = OnceLock::new();
+  RE.get_or_init(|| Regex::new(r"^\s*permissions\s*:").expect("valid workflow pattern"))
+}
+
+/// A YAML file with the `ci_workflow` role. Other CI systems' files get the
+/// same checks; they have no `permissions:` or `pull_request_target` keys.
+fn is_workflow(file: &FileDiff, classes: &[FileClass]) -> bool {
+  let class = classes
+    .iter()
+    .find(|c| c.path == file.path)
+    .cloned()
+    .unwrap_or_else(|| classify(&file.path));
+  class.role == FileRole::CiWorkflow && class.language.as_deref() == Some("yaml")
+}
+
+/// The line without a trailing `# comment`.
+fn strip_comment(text: &str) -> &str {
+  if text.trim_start().starts_with('#') {
+    return "";
+  }
+  text.split(" #").next().unwrap_or(text)
+}
+
+/// Check every CI workflow in the diff.
+pub fn lint(diffs: &[FileDiff], classes: &[FileClass]) -> Vec<WorkflowFinding> {
+  let mut out = Vec::new();
+  for file in diffs {
+    if file.binary || !is_workflow(file, classes) {
+      continue;
+    }
+    let lines: Vec<_> = file.hunks.iter().flat_map(|h| &h.lines).collect();
+    let removed: Vec<&str> = lines
+      .iter()
+      .filter(|l| l.kind == LineKind::Removed)
+      .map(|l| strip_comment(&l.text).trim())
+      .collect();
+    let mut push = |line: u32, rule: &str, severity: Severity, message: String, text: &str| {
+      out.push(WorkflowFinding {
+        path: file.path.clone(),
+        line,
+        rule: rule.to_string(),
+        severity,
+        message,
+        snippet: snippet(text.trim()),
+      })
+    };
+
+    for line in file.added_lines() {
+      let code = strip_comment(&line.text);
+      let at = line.new_line.unwrap_or(0);
+      if pull_request_target().is_match(code) {
+        push(
+          at,
+          "pull_request_target",
+          Severity::High,
+          "pull_request_target runs fork pull requests with a write token and secrets".to_string(),
+          code,
+        );
+      }
+      if write_all().is_match(code) {
+        push(
+          at,
+          "permissions_write_all",
+          Severity::High,
+          "permissions: write-all grants the workflow token every write scope".to_string(),
+          code,
+        );
+      } else if let Some(caps) = scope_write().captures(code) {
+        let scope = &caps[1];
+        // A grant that was only moved or re-indented isn't a broadening.
+        if SCOPES.contains(&scope) && !removed.contains(&code.trim()) {
+          push(
+            at,
+            "permission_write",
+            Severity::Medium,
+            format!("Workflow token granted {}: write", scope),
+            code,
+          );
+        }
+      }
+    }
+
+    let kept_permissions = file
+      .added_lines()
+      .any(|l| permissions_key().is_match(strip_comment(&l.text)));
+    if !kept_permissions {
+      if let Some(line) = lines
+        .iter()
+        .find(|l| l.kind == LineKind::Removed && permissions_key().is_match(strip_comment(&l.text)))
+      {
+        push(
+          line.old_line.unwrap_or(0),
+          "permissions_removed",
+          Severity::Medium,
+          "permissions: removed, so the token falls back to the repository default".to_string(),
+          &line.text,
+        );
+      }
+    }
+  }
+  out
+}
+
+/// `ci` hit from the worst finding (medium or high), if any.
+pub fn workflow_hit(findings: &[WorkflowFinding]) -> Option<FlagHit> {
+  worst_finding_hit(RiskFlag::Ci, "workflow", "Risky workflow change", findings)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::classify::classify_all;
+  use crate::diff::parse_unified_diff;
+
+  fn findings(diff: &str) -> Vec<WorkflowFinding> {
+    let diffs = parse_unified_diff(diff);
+    let paths: Vec<String> = diffs.iter().map(|d| d.path.clone()).collect();
+    lint(&diffs, &classify_all(&paths))
+  }
+
+  fn rules(diff: &str) -> Vec<(String, u32)> {
+    findings(diff)
+      .into_iter()
+      .map(|f| (f.rule, f.line))
+      .collect()
+  }
+
+  #[test]
+  fn flags_privileged_triggers_and_write_grants() {
+    let diff = "diff --git a/.github/workflows/ci.yml b/.github/workflows/ci.yml\n--- a/.github/workflows/ci.yml\n+++ b/.github/workflows/ci.yml\n@@ -1,5 +1,6 @@\n on:\n-  pull_request:\n+  pull_request_target:\n permissions:\n-  contents: read\n+  contents: write\n+  id-token: write # for OIDC\n jobs:\n";
+    assert_eq!(
+      rules(diff),
+      vec![
+        ("pull_request_target".to_string(), 2),
+        ("permission_write".to_string(), 4),
+        ("permission_write".to_string(), 5),
+      ]
+    );
+    let hit = workflow_hit(&findings(diff)).unwrap();
+    assert_eq!(hit.flag, RiskFlag::Ci);
+    assert_eq!(
+      (hit.rule.as_str(), hit.weight),
+      ("workflow-pull-request-target", 25)
+    );
+  }
+
+  #[test]
+  fn write_all_and_removed_permissions() {
+    let diff = "--- a/.github/workflows/release.yaml\n+++ b/.github/workflows/release.yaml\n@@ -3,4 +3,2 @@\n jobs:\n-  permissions:\n-    contents: read\n   build:\n--- a/.github/workflows/bot.yml\n+++ b/.github/workflows/bot.yml\n@@ -1,1 +1,1 @@\n-permissions: read-all\n+permissions: write-all\n";
+    assert_eq!(
+      rules(diff),
+      vec![
+        ("permissions_removed".to_string(), 4),
+        ("permissions_write_all".to_string(), 1),
+      ]
+    );
+  }
+
+  #[test]
+  fn ignores_moved_grants_comments_and_other_yaml() {
+    let diff = "--- a/.github/workflows/ci.yml\n+++ b/.github/workflows/ci.yml\n@@ -1,2 +1,3 @@\n-permissions:\n-  contents: write\n+permissions:\n+    contents: write\n+# pull_request_target is not used here\n--- a/config/app.yml\n+++ b/config/app.yml\n@@ -1,0 +1,1 @@\n+contents: write\n";
+    assert!(rules(diff).is_empty());
+  }
+}
@@ -0,0 +1,189 @@
+//! Binary tests: run the built risk-engine and check its stdout contract.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run_bin(args: &[&str], stdin: &str) -> (Option<i32>, String) {
+  run_bin_with_env(args, &[], stdin)
+}
+
+fn run_bin_with_env(args: &[&str], env: &[(&str, &str)], stdin: &str) -> (Option<i32>, String) {
+  let mut command = Command::new(env!("CARGO_BIN_EXE_risk-engine"));
+  for var in [
+    "RISK_ENGINE_RULES",
+    "RISK_ENGINE_MODEL",
+    "RISK_ENGINE_SCORER",
+    "RISK_ENGINE_MAX_INPUT_BYTES",
+    "RISK_ENGINE_MAX_FILES",
+    "RISK_ENGINE_MAX_DIFF_BYTES",
+    "RISK_ENGINE_MAX_COMMITS",
+  ] {
+    command.env_remove(var);
+  }
+  let mut child = command
+    .args(args)
+    .envs(env.iter().copied())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .expect("spawn risk-engine");
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(stdin.as_bytes())
+    .unwrap();
+  let out = child.wait_with_output().unwrap();
+  (out.status.code(), String::from_utf8(out.stdout).unwrap())
+}
+
+#[test]
+fn stream_mode_answers_each_line_with_its_id() {
+  let input = concat!(
+    r#"{"id":"p1","commit_message":"feat: x","files_changed":["src/auth.ts"],"additions":1,"deletions":0}"#,
+    "\n\n",
+    "not json\n",
+    r#"{"id":2,"commit_message":"fix: y"}"#,
+    "\n",
+  );
+  let (code, stdout) = run_bin(&["--stream"], input);
+  assert_eq!(code, Some(0));
+  let lines: Vec<serde_json::Value> = stdout
+    .lines()
+    .map(|l| serde_json::from_str(l).unwrap())
+    .collect();
+  assert_eq!(lines.len(), 3);
+  assert_eq!(lines[0]["id"], "p1");
+  assert_eq!(lines[0]["risk_flags"][0], "auth");
+  assert_eq!(lines[1]["error"], true);
+  assert_eq!(lines[2]["id"], 2);
+  assert_eq!(lines[2]["error"], true);
+}
+
+#[test]
+fn single_shot_mode_writes_one_object() {
+  let (code, stdout) = run_bin(
+    &[],
+    r#"{"commit_message":"docs: readme","files_changed":["README.md"],"additions":3,"deletions":1}"#,
+  );
+  assert_eq!(code, Some(0));
+  let out: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(out["change_type_tags"][0], "docs");
+}
+
+#[test]
+fn schema_flag_describes_every_output_field() {
+  let (code, stdout) = run_bin(&["--schema"], "");
+  assert_eq!(code, Some(0));
+  let schema: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  let input_required = schema["input"]["required"].as_array().unwrap();
+  assert!(input_required.contains(&"files_changed".into()));
+
+  let (_, stdout) = run_bin(
+    &[],
+    r#"{"commit_message":"feat: x","files_changed":["src/a.ts"],"additions":1,"deletions":0}"#,
+  );
+  let out: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(out["schema_version"], schema["schema_version"]);
+  let properties = schema["output"]["properties"].as_object().unwrap();
+  for key in out.as_object().unwrap().keys() {
+    assert!(properties.contains_key(key), "{} missing from schema", key);
+  }
+}
+
+#[test]
+fn invalid_input_writes_an_error_object_and_exits_2() {
+  let (code, stdout) = run_bin(&[], r#"{"commit_message":"fix: x"}"#);
+  assert_eq!(code, Some(2));
+  let err: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(err["error"], true);
+  assert_eq!(err["field"], "files_changed");
+
+  let (code, stdout) = run_bin_with_env(
+    &[],
+    &[("RISK_ENGINE_MAX_FILES", "1")],
+    r#"{"commit_message":"fix: x","files_changed":["a.ts","b.ts"],"additions":1,"deletions":0}"#,
+  );
+  assert_eq!(code, Some(2));
+  let err: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(err["field"], "files_changed");
+  assert_eq!(err["message"], "2 paths exceeds the limit of 1");
+}
+
+#[test]
+fn oversized_stream_line_is_rejected_and_the_rest_answered() {
+  let big = format!(
+    r#"{{"id":"big","commit_message":"{}","files_changed":[],"additions":0,"deletions":0}}"#,
+    "x".repeat(500)
+  );
+  let input = format!(
+    "{}\n{}\n",
+    big,
+    r#"{"id":"ok","commit_message":"fix: y","files_changed":["a.ts"],"additions":1,"deletions":0}"#
+  );
+  let (code, stdout) = run_bin_with_env(
+    &["--stream"],
+    &[("RISK_ENGINE_MAX_INPUT_BYTES", "200")],
+    &input,
+  );
+  assert_eq!(code, Some(0));
+  let lines: Vec<serde_json::Value> = stdout
+    .lines()
+    .map(|l| serde_json::from_str(l).unwrap())
+    .collect();
+  assert_eq!(lines[0]["error"], true);
+  assert_eq!(lines[1]["id"], "ok");
+}
+
+#[test]
+fn bad_limit_configuration_exits_1() {
+  let (code, stdout) = run_bin_with_env(&[], &[("RISK_ENGINE_MAX_FILES", "lots")], "{}");
+  assert_eq!(code, Some(1));
+  let err: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert!(err["message"]
+    .as_str()
+    .unwrap()
+    .contains("RISK_ENGINE_MAX_FILES"));
+}
+
+#[test]
+fn scorer_variable_selects_the_scorer() {
+  let input =
+    r#"{"commit_message":"feat: x","files_changed":["src/auth.ts"],"additions":10,"deletions":0}"#;
+  let (code, stdout) = run_bin_with_env(&[], &[("RISK_ENGINE_SCORER", "legacy")], input);
+  assert_eq!(code, Some(0));
+  let out: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(
+    (out["scorer"].as_str(), out["model_version"].as_str()),
+    (Some("legacy"), Some("1"))
+  );
+
+  let (_, stdout) = run_bin(&[], input);
+  let out: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(out["scorer"], "weighted");
+
+  let model = std::env::temp_dir().join(format!("risk-model-{}.json", std::process::id()));
+  std::fs::write(&model, r#"{"version":"fit-2026-10","intercept":-2.0}"#).unwrap();
+  let (code, stdout) = run_bin_with_env(
+    &[],
+    &[
+      ("RISK_ENGINE_SCORER", "logistic"),
+      ("RISK_ENGINE_MODEL", model.to_str().unwrap()),
+    ],
+    input,
+  );
+  std::fs::remove_file(&model).unwrap();
+  assert_eq!(code, Some(0));
+  let out: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert_eq!(out["scorer"], "logistic");
+  assert_eq!(out["model_version"], "fit-2026-10");
+
+  let (code, stdout) = run_bin_with_env(&[], &[("RISK_ENGINE_SCORER", "logistic")], input);
+  assert_eq!(code, Some(1));
+  let err: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert!(err["message"]
+    .as_str()
+    .unwrap()
+    .contains("RISK_ENGINE_SCORER"));
+}
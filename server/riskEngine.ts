@@ -18,16 +18,215 @@ export interface ScorePushInput {
   filesChanged: string[];
   additions: number;
   deletions: number;
+  /** Optional per-file status from GitHub (compare / PR files API); marks renames. */
+  fileChanges?: FileChangeEntry[];
   diffText?: string;
+  /** Optional .gitattributes contents (linguist-generated / linguist-vendored). */
+  gitattributes?: string;
+  /** Optional CODEOWNERS contents; maps changed paths to owners. */
+  codeowners?: string;
+  /** Optional per-file history snapshot (change frequency, incidents, authors). */
+  history?: FileHistoryEntry[];
+  /** Optional per-commit breakdown of a multi-commit push. */
+  commits?: ScorePushCommit[];
 }
 
+export interface FileChangeEntry {
+  path: string;
+  /** GitHub file status: "added", "removed", "modified", "renamed", ... */
+  status?: string;
+  /** GitHub's previous_filename, for renames and moves. */
+  previousPath?: string;
+  additions?: number;
+  deletions?: number;
+}
+
+export interface FileHistoryEntry {
+  path: string;
+  changeCount: number;
+  incidentCount: number;
+  authorCount: number;
+}
+
+export interface ScorePushCommit {
+  id: string;
+  message: string;
+  files: string[];
+  additions: number;
+  deletions: number;
+  diffText?: string;
+}
+
+/** Output contract version this module was written against. */
+export const RISK_SCHEMA_VERSION = 1;
+
 /** Output from Rust (snake_case). */
 export interface RiskResult {
+  /** Output contract version; 0 for the fallback result. */
+  schema_version: number;
   impact_score: number;
+  /** Scorer that produced impact_score ("weighted", "legacy" or "logistic") and its model version. */
+  scorer: string;
+  model_version: string;
   risk_flags: string[];
   change_type_tags: string[];
+  /** SHAs from "This reverts commit <sha>" lines. */
+  reverted_commits: string[];
   hotspot_files: string[];
+  hotspots: Hotspot[];
   explanations: string[];
+  commit_type: string | null;
+  commit_scope: string | null;
+  breaking_change: boolean;
+  flag_hits: FlagHit[];
+  owners: string[];
+  file_owners: FileOwners[];
+  file_classes: FileClass[];
+  excluded_files: ExcludedFile[];
+  renamed_files: RenamedFile[];
+  secret_findings: SecretFinding[];
+  dependency_changes: DependencyChange[];
+  migration_findings: MigrationFinding[];
+  workflow_findings: WorkflowFinding[];
+  api_changes: ApiChange[];
+  routes: RouteChange[];
+  test_coverage: TestCoverage[];
+  commits: CommitScore[];
+  push_rollup: PushRollup | null;
+}
+
+export interface CommitScore {
+  id: string;
+  impact_score: number;
+  risk_flags: string[];
+  change_type_tags: string[];
+  reverted_commits: string[];
+  commit_type: string | null;
+  breaking_change: boolean;
+}
+
+export interface PushRollup {
+  commit_count: number;
+  max_impact_score: number;
+  mean_impact_score: number;
+  riskiest_commit: string | null;
+  risky_commits: string[];
+}
+
+/** Why a flag was raised: matching rule, paths, and points added to impact_score. */
+export type FlagSeverity = "info" | "low" | "medium" | "high" | "critical";
+
+export interface FlagHit {
+  flag: string;
+  severity: FlagSeverity;
+  rule: string;
+  weight: number;
+  contribution: number;
+  explanation: string;
+  paths: string[];
+}
+
+/** Path classification from the engine (role e.g. "source", "test", "lockfile", "iac", "ci_workflow"). */
+export interface FileClass {
+  path: string;
+  language: string | null;
+  role: string;
+  ecosystem: string | null;
+}
+
+/** CODEOWNERS owners of one changed path; empty when unowned. */
+export interface FileOwners {
+  path: string;
+  owners: string[];
+}
+
+/** Hotspot rank inputs: this push's churn × historical instability. */
+export interface Hotspot {
+  path: string;
+  churn: number;
+  change_count: number;
+  incident_count: number;
+  author_count: number;
+  score: number;
+}
+
+/** File left out of the impact score (generated, vendored, lockfile). */
+export interface ExcludedFile {
+  path: string;
+  reason: string;
+}
+
+/** File renamed or moved; `pure` when no lines were edited (not counted, never a hotspot). */
+export interface RenamedFile {
+  path: string;
+  previous_path: string;
+  churn: number;
+  pure: boolean;
+}
+
+/** Package added, removed or re-versioned in a manifest diff. */
+export interface DependencyChange {
+  path: string;
+  ecosystem: string;
+  name: string;
+  change: "added" | "removed" | "major" | "minor" | "patch" | "downgrade" | "changed";
+  from: string | null;
+  to: string | null;
+  direct: boolean;
+}
+
+/** Exported / public declaration or HTTP route added, removed or re-signed. */
+export interface ApiChange {
+  path: string;
+  /** Declaration name, or "METHOD /path" for routes. */
+  symbol: string;
+  kind: "function" | "class" | "type" | "const" | "route";
+  change: "added" | "removed" | "changed";
+  line: number;
+}
+
+/** Source file whose logic changed, with the changed tests that pair with it (empty = untested). */
+export interface TestCoverage {
+  path: string;
+  tests: string[];
+}
+
+/** HTTP route touched by the diff; `route` joins with incidents' `api_route`. */
+export interface RouteChange {
+  path: string;
+  /** Upper-case method, or "ANY" / "ALL". */
+  method: string;
+  route: string;
+  change: "added" | "removed" | "changed" | "modified";
+  line: number;
+}
+
+/** Destructive or locking statement found in a SQL / migration diff. */
+export interface MigrationFinding {
+  path: string;
+  line: number;
+  rule: string;
+  severity: "high" | "medium";
+  message: string;
+  snippet: string;
+}
+
+/** Risky trigger or token-permission edit in a GitHub Actions workflow. */
+export interface WorkflowFinding {
+  path: string;
+  line: number;
+  rule: "pull_request_target" | "permissions_write_all" | "permission_write" | "permissions_removed";
+  severity: "high" | "medium";
+  message: string;
+  snippet: string;
+}
+
+/** Suspected credential in an added diff line; `snippet` has the secret masked. */
+export interface SecretFinding {
+  path: string;
+  line: number;
+  kind: string;
+  snippet: string;
 }
 
 function getBinaryPath(): string {
@@ -40,6 +239,75 @@ function getBinaryPath(): string {
   return release;
 }
 
+/** Error object the binary writes to stdout on failure (and per bad stream line). */
+export interface EngineErrorOutput {
+  error: true;
+  message: string;
+  /** Input field at fault, when known (e.g. "files_changed", "commits[2].diff_text"). */
+  field?: string;
+}
+
+/** Binary exit codes (see risk-engine/src/main.rs). */
+const EXIT_REASONS: Record<number, string> = {
+  1: "bad configuration",
+  2: "invalid input",
+  3: "I/O failure",
+  4: "internal panic",
+};
+
+function parseEngineError(raw: string): EngineErrorOutput | null {
+  try {
+    const parsed = JSON.parse(raw.trim());
+    return parsed && parsed.error === true ? (parsed as EngineErrorOutput) : null;
+  } catch {
+    return null;
+  }
+}
+
+/** Warn once per process when the binary speaks a different output contract. */
+let schemaWarned = false;
+function warnOnSchemaMismatch(result: RiskResult): void {
+  if (schemaWarned || result.schema_version === RISK_SCHEMA_VERSION) return;
+  schemaWarned = true;
+  console.warn(
+    `[risk-engine] Output schema_version ${result.schema_version ?? "missing"}, expected ${RISK_SCHEMA_VERSION}; rebuild the binary`,
+  );
+}
+
+/** Safe result used when the engine fails or times out. */
+function emptyResult(): RiskResult {
+  return {
+    schema_version: 0,
+    impact_score: 0,
+    scorer: "none",
+    model_version: "",
+    risk_flags: [],
+    change_type_tags: [],
+    reverted_commits: [],
+    hotspot_files: [],
+    hotspots: [],
+    explanations: [],
+    commit_type: null,
+    commit_scope: null,
+    breaking_change: false,
+    flag_hits: [],
+    owners: [],
+    file_owners: [],
+    file_classes: [],
+    excluded_files: [],
+    renamed_files: [],
+    secret_findings: [],
+    dependency_changes: [],
+    migration_findings: [],
+    workflow_findings: [],
+    api_changes: [],
+    routes: [],
+    test_coverage: [],
+    commits: [],
+    push_rollup: null,
+  };
+}
+
 /** Build the JSON object Rust expects (snake_case). */
 function toRustInput(input: ScorePushInput): Record<string, unknown> {
   return {
@@ -47,7 +315,36 @@ function toRustInput(input: ScorePushInput): Record<string, unknown> {
     files_changed: input.filesChanged,
     additions: input.additions,
     deletions: input.deletions,
+    ...(input.fileChanges != null && {
+      file_changes: input.fileChanges.map((f) => ({
+        path: f.path,
+        ...(f.status != null && { status: f.status }),
+        ...(f.previousPath != null && { previous_path: f.previousPath }),
+        ...(f.additions != null && { additions: f.additions }),
+        ...(f.deletions != null && { deletions: f.deletions }),
+      })),
+    }),
     ...(input.diffText != null && { diff_text: input.diffText }),
+    ...(input.gitattributes != null && { gitattributes: input.gitattributes }),
+    ...(input.codeowners != null && { codeowners: input.codeowners }),
+    ...(input.history != null && {
+      history: input.history.map((h) => ({
+        path: h.path,
+        change_count: h.changeCount,
+        incident_count: h.incidentCount,
+        author_count: h.authorCount,
+      })),
+    }),
+    ...(input.commits != null && {
+      commits: input.commits.map((c) => ({
+        id: c.id,
+        message: c.message,
+        files: c.files,
+        additions: c.additions,
+        deletions: c.deletions,
+        ...(c.diffText != null && { diff_text: c.diffText }),
+      })),
+    }),
   };
 }
 
@@ -69,13 +366,7 @@ export async function scorePush(input: ScorePushInput): Promise<RiskResult> {
     const chunks: Buffer[] = [];
     child.stdout?.on("data", (chunk: Buffer) => chunks.push(chunk));
 
-    const fallback: RiskResult = {
-      impact_score: 0,
-      risk_flags: [],
-      change_type_tags: [],
-      hotspot_files: [],
-      explanations: [],
-    };
+    const fallback = emptyResult();
 
     const done = (result: RiskResult) => {
       if (!child.killed) child.kill();
@@ -96,13 +387,21 @@ export async function scorePush(input: ScorePushInput): Promise<RiskResult> {
     child.on("close", (code, signal) => {
       clearTimeout(timeout);
       if (code !== 0) {
-        console.warn("[risk-engine] Exit", code, signal);
+        const err = parseEngineError(Buffer.concat(chunks).toString("utf8"));
+        console.warn(
+          "[risk-engine] Exit",
+          code,
+          signal,
+          EXIT_REASONS[code ?? -1] ?? "",
+          err ? `${err.message}${err.field ? ` (field: ${err.field})` : ""}` : "",
+        );
         done(fallback);
         return;
       }
       try {
         const raw = Buffer.concat(chunks).toString("utf8").trim();
         const parsed = JSON.parse(raw) as RiskResult;
+        warnOnSchemaMismatch(parsed);
         done(parsed);
       } catch (e) {
         console.warn("[risk-engine] Parse error:", e);
@@ -115,3 +414,84 @@ export async function scorePush(input: ScorePushInput): Promise<RiskResult> {
     });
   });
 }
+
+/**
+ * Score many pushes with one `risk-engine --stream` process (for backfills).
+ * Results are returned in input order; entries that fail or time out get the
+ * fallback result (does not throw).
+ */
+export async function scorePushBatch(inputs: ScorePushInput[]): Promise<RiskResult[]> {
+  const results: RiskResult[] = inputs.map(() => emptyResult());
+  if (inputs.length === 0) return results;
+
+  const bin = getBinaryPath();
+  const perItemMs = Number(process.env.RISK_ENGINE_TIMEOUT_MS) || DEFAULT_TIMEOUT_MS;
+  const timeoutMs = perItemMs + inputs.length * 50;
+
+  return new Promise((resolve) => {
+    const child = spawn(bin, ["--stream"], {
+      stdio: ["pipe", "pipe", "pipe"],
+      cwd: path.join(__dirname, ".."),
+    });
+
+    let pending = inputs.length;
+    let buffered = "";
+
+    const done = () => {
+      clearTimeout(timeout);
+      if (!child.killed) child.kill();
+      resolve(results);
+    };
+
+    const timeout = setTimeout(() => {
+      console.warn(`[risk-engine] Stream timeout with ${pending} pending, using fallback`);
+      done();
+    }, timeoutMs);
+
+    const handleLine = (line: string) => {
+      if (!line.trim()) return;
+      try {
+        const parsed = JSON.parse(line) as RiskResult & { id?: number; error?: boolean; message?: string };
+        if (typeof parsed.id !== "number" || parsed.id < 0 || parsed.id >= inputs.length) return;
+        if (parsed.error) {
+          console.warn("[risk-engine] Stream item error:", parsed.id, parsed.message);
+        } else {
+          const { id: _id, ...result } = parsed;
+          warnOnSchemaMismatch(result as RiskResult);
+          results[parsed.id] = result as RiskResult;
+        }
+        pending -= 1;
+        if (pending === 0) done();
+      } catch (e) {
+        console.warn("[risk-engine] Stream parse error:", e);
+      }
+    };
+
+    child.stdout?.on("data", (chunk: Buffer) => {
+      buffered += chunk.toString("utf8");
+      let idx: number;
+      while ((idx = buffered.indexOf("\n")) >= 0) {
+        handleLine(buffered.slice(0, idx));
+        buffered = buffered.slice(idx + 1);
+      }
+    });
+
+    child.on("error", (err) => {
+      console.warn("[risk-engine] Spawn error:", err.message);
+      done();
+    });
+
+    child.on("close", (code, signal) => {
+      if (buffered) handleLine(buffered);
+      if (code !== 0) console.warn("[risk-engine] Stream exit", code, signal);
+      done();
+    });
+
+    const payload = inputs
+      .map((input, id) => JSON.stringify({ id, ...toRustInput(input) }))
+      .join("\n");
+    child.stdin?.write(payload + "\n", "utf8", () => {
+      child.stdin?.end();
+    });
+  });
+}
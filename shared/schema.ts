@@ -216,6 +216,18 @@ export const pushEvents = pgTable("push_events", {
     change_type_tags?: string[];
     hotspot_files?: string[];
     explanations?: string[];
+    /** Per-flag rule, weight and score contribution (risk engine `flag_hits`). */
+    flag_hits?: {
+      flag: string;
+      severity: string;
+      rule: string;
+      weight: number;
+      contribution: number;
+      explanation: string;
+      paths: string[];
+    }[];
+    /** CODEOWNERS owners of the changed paths (risk engine `owners`). */
+    owners?: string[];
     /** Optional: compact diff enrichment audit from push webhook (see server/pushDiffEnrichment.ts). */
     diff_enrichment?: {
       used: boolean;
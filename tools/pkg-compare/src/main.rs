@@ -13,6 +13,7 @@ use std::fs;
 use std::process;
 
 type Deps = BTreeMap<String, String>;
+type Changed = Vec<(String, String, String)>;
 
 #[derive(serde::Deserialize, Default)]
 struct PackageJson {
@@ -42,7 +43,7 @@ fn flatten(deps: &Deps, dev: &Deps) -> Deps {
     all
 }
 
-fn compare(all_a: &Deps, all_b: &Deps) -> (Vec<String>, Vec<String>, Vec<(String, String, String)>) {
+fn compare(all_a: &Deps, all_b: &Deps) -> (Vec<String>, Vec<String>, Changed) {
     let mut only_a = Vec::new();
     let mut only_b = Vec::new();
     let mut changed = Vec::new();
@@ -75,8 +76,8 @@ fn main() {
         process::exit(2);
     }
 
-    let (deps_a, dev_a) = load_deps(&files[0]);
-    let (deps_b, dev_b) = load_deps(&files[1]);
+    let (deps_a, dev_a) = load_deps(files[0]);
+    let (deps_b, dev_b) = load_deps(files[1]);
     let all_a = flatten(&deps_a, &dev_a);
     let all_b = flatten(&deps_b, &dev_b);
     let (only_a, only_b, changed) = compare(&all_a, &all_b);
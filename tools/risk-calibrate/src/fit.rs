@@ -0,0 +1,140 @@
+//! L2-regularized logistic regression, fitted with Newton's method (IRLS).
+//!
+//! Feature counts are small (two numeric features plus one column per risk
+//! flag), so each step solves the full d×d system directly.
+
+/// Max Newton steps; well-conditioned problems converge in under 20.
+const MAX_ITERATIONS: usize = 50;
+/// Stop when no weight moves more than this.
+const TOLERANCE: f64 = 1e-8;
+
+/// Fit weights for `rows` (without an intercept column) against `labels`.
+/// Returns `[intercept, w_1, ..., w_d]`. `l2` penalizes every weight but the
+/// intercept, which keeps flags that always (or never) precede an incident
+/// from running off to infinity.
+pub fn fit_logistic(rows: &[Vec<f64>], labels: &[bool], l2: f64) -> Vec<f64> {
+    let d = rows.first().map_or(0, Vec::len) + 1;
+    let mut w = vec![0.0; d];
+    for _ in 0..MAX_ITERATIONS {
+        let mut grad = vec![0.0; d];
+        let mut hess = vec![vec![0.0; d]; d];
+        for (row, &label) in rows.iter().zip(labels) {
+            let x: Vec<f64> = std::iter::once(1.0).chain(row.iter().copied()).collect();
+            let p = sigmoid(dot(&w, &x));
+            let y = if label { 1.0 } else { 0.0 };
+            let s = p * (1.0 - p);
+            for i in 0..d {
+                grad[i] += (y - p) * x[i];
+                for j in 0..d {
+                    hess[i][j] += s * x[i] * x[j];
+                }
+            }
+        }
+        for i in 1..d {
+            grad[i] -= l2 * w[i];
+            hess[i][i] += l2;
+        }
+        // Tiny ridge on the intercept so an all-one-label set stays solvable.
+        hess[0][0] += 1e-9;
+        let Some(step) = solve(hess, grad) else {
+            break;
+        };
+        let mut moved = 0.0f64;
+        for (wi, si) in w.iter_mut().zip(&step) {
+            *wi += si;
+            moved = moved.max(si.abs());
+        }
+        if moved < TOLERANCE {
+            break;
+        }
+    }
+    w
+}
+
+/// Linear approximation of `100 × σ(intercept + w · x)` around `mean`, so
+/// fitted weights read as points on a 0–100 score. Returns the score at
+/// `x = 0` and the points per unit of each feature.
+pub fn to_points(weights: &[f64], mean: &[f64]) -> (f64, Vec<f64>) {
+    let p = sigmoid(weights[0] + dot(&weights[1..], mean));
+    let slope = 100.0 * p * (1.0 - p);
+    let points: Vec<f64> = weights[1..].iter().map(|w| w * slope).collect();
+    (100.0 * p - dot(&points, mean), points)
+}
+
+pub fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Solve `a · x = b` by Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let f = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= f * p;
+            }
+            b[col + 1 + offset] -= f * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn risky_flag_gets_positive_weight() {
+        // Column 0: flag present. Incidents follow 3/4 flagged, 1/4 unflagged.
+        let mut rows = Vec::new();
+        let mut labels = Vec::new();
+        for i in 0..40 {
+            let flagged = i % 2 == 0;
+            rows.push(vec![if flagged { 1.0 } else { 0.0 }]);
+            labels.push(if flagged { i % 8 != 0 } else { i % 8 == 1 });
+        }
+        let w = fit_logistic(&rows, &labels, 0.0);
+        // Unregularized fit recovers the log-odds: ln(1/3) and ln(9).
+        assert!((w[0] - (1.0f64 / 3.0).ln()).abs() < 1e-6, "{:?}", w);
+        assert!((w[1] - 9.0f64.ln()).abs() < 1e-6, "{:?}", w);
+    }
+
+    #[test]
+    fn points_match_the_score_near_the_mean() {
+        let weights = [-1.0, 0.2, 0.8];
+        let mean = [3.0, 0.5];
+        let (base, points) = to_points(&weights, &mean);
+        let score = |x: [f64; 2]| 100.0 * sigmoid(weights[0] + dot(&weights[1..], &x));
+        let linear = |x: [f64; 2]| base + dot(&points, &x);
+        assert!((linear(mean) - score(mean)).abs() < 1e-9);
+        assert!((linear([3.1, 0.5]) - score([3.1, 0.5])).abs() < 0.01);
+        assert!(points[1] > points[0]);
+    }
+
+    #[test]
+    fn l2_keeps_separable_data_finite() {
+        let rows = vec![vec![1.0], vec![1.0], vec![0.0], vec![0.0]];
+        let labels = vec![true, true, false, false];
+        let w = fit_logistic(&rows, &labels, 1.0);
+        assert!(w.iter().all(|x| x.is_finite()));
+        assert!(w[1] > 0.0);
+    }
+}
@@ -0,0 +1,374 @@
+//! risk-calibrate: fit risk-engine scoring weights from labeled push history
+//!
+//! Usage:
+//!   risk-calibrate <history.jsonl> [options]
+//!
+//! Each input line is a risk-engine `Input` plus `"incident": true|false`
+//! (whether an incident followed the push within the export's window).
+//! Two fits share one column per flag in `flag_hits` (discounts such as
+//! tests_updated included):
+//!
+//! - the legacy formula's shape, on min(files, 30) and min(churn / 10, 40),
+//!   reported as points on its 0–100 scale next to the hard-coded weights
+//!   (2 per file, 1 per 10 lines, +15/+10/+5 per flag);
+//! - a logistic model on ln(1 + files) and ln(1 + churn), for
+//!   RISK_ENGINE_MODEL.
+//!
+//! The JSON report compares both with the original formula (AUC, precision
+//! and recall at a score threshold) on held-out pushes. With `--model-out`,
+//! writes the fitted logistic model.
+//!
+//! Options:
+//!   --threshold N       score counted as "risky" for precision/recall (default 50)
+//!   --holdout-every N   every Nth push is held out for evaluation (default 5; 0 = none)
+//!   --l2 X              L2 penalty on non-intercept weights (default 1.0)
+//!   --model-version V   version written into the model (default "calibrated")
+//!   --model-out PATH    write the fitted model as JSON
+
+mod fit;
+mod metrics;
+
+use metrics::Metrics;
+use risk_engine::types::FlagHit;
+use risk_engine::{
+    run_with_config, score_features, Config, Input, LegacyScorer, LogisticModel, LogisticScorer,
+    RiskFlag, ScoreFeatures, Scorer,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::process;
+use std::sync::Arc;
+
+/// One exported push and whether an incident followed it.
+#[derive(Deserialize)]
+struct Labeled {
+    #[serde(flatten)]
+    input: Input,
+    incident: bool,
+}
+
+/// What the fit and the evaluation need from one push.
+struct Example {
+    features: ScoreFeatures,
+    /// Every flag hit, discounts included.
+    hits: Vec<FlagHit>,
+    /// The original formula's score.
+    legacy_score: u8,
+    incident: bool,
+}
+
+/// An additive score in the legacy formula's units.
+#[derive(Serialize)]
+struct Weights {
+    /// Score with no files, churn or flags.
+    base: f64,
+    /// Points per changed file, for the first 30.
+    files: f64,
+    /// Points per 10 changed lines, for the first 400.
+    churn: f64,
+    /// Points per flag; flags missing here add nothing.
+    flags: BTreeMap<String, f64>,
+}
+
+impl Weights {
+    /// The hard-coded weights, read off the legacy scorer itself.
+    fn legacy() -> Self {
+        let score = |features: ScoreFeatures, hits: &mut [FlagHit]| {
+            LegacyScorer.score(&features, hits) as f64
+        };
+        let flags = RiskFlag::BUILTIN
+            .iter()
+            .map(|flag| {
+                let hit = FlagHit::new(flag.clone(), "", 0, "");
+                (
+                    flag.to_string(),
+                    score(ScoreFeatures::default(), &mut [hit]),
+                )
+            })
+            .filter(|(_, points)| *points != 0.0)
+            .collect();
+        Self {
+            base: 0.0,
+            files: score(
+                ScoreFeatures {
+                    listed_files: 1,
+                    ..ScoreFeatures::default()
+                },
+                &mut [],
+            ),
+            churn: score(
+                ScoreFeatures {
+                    reported_churn: 10,
+                    ..ScoreFeatures::default()
+                },
+                &mut [],
+            ),
+            flags,
+        }
+    }
+
+    fn fitted(weights: &[f64], mean: &[f64], flags: &[String]) -> Self {
+        let (base, points) = fit::to_points(weights, mean);
+        Self {
+            base: round(base),
+            files: round(points[0]),
+            churn: round(points[1]),
+            flags: flags
+                .iter()
+                .cloned()
+                .zip(points[2..].iter().map(|p| round(*p)))
+                .collect(),
+        }
+    }
+
+    fn score(&self, example: &Example, flags: &[String]) -> u8 {
+        let x = points_row(example, flags);
+        let mut score = self.base + self.files * x[0] + self.churn * x[1];
+        for (flag, present) in flags.iter().zip(&x[2..]) {
+            score += self.flags.get(flag).copied().unwrap_or(0.0) * present;
+        }
+        score.round().clamp(0.0, 100.0) as u8
+    }
+}
+
+fn round(x: f64) -> f64 {
+    // `+ 0.0` turns -0.0 into 0.0 in the report.
+    (x * 10.0).round() / 10.0 + 0.0
+}
+
+struct Options {
+    path: String,
+    threshold: u8,
+    holdout_every: usize,
+    l2: f64,
+    model_version: String,
+    model_out: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    examples: usize,
+    incidents: usize,
+    train: usize,
+    /// Pushes the metrics below were computed on (the training set when
+    /// nothing is held out).
+    evaluated: usize,
+    /// The original formula.
+    legacy: Metrics,
+    /// The legacy formula with `fitted_weights`.
+    reweighted: Metrics,
+    /// The logistic model.
+    fitted: Metrics,
+    legacy_weights: Weights,
+    fitted_weights: Weights,
+    model: LogisticModel,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: risk-calibrate <history.jsonl> [--threshold N] [--holdout-every N] [--l2 X] [--model-version V] [--model-out PATH]");
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut opts = Options {
+        path: String::new(),
+        threshold: 50,
+        holdout_every: 5,
+        l2: 1.0,
+        model_version: "calibrated".to_string(),
+        model_out: None,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--threshold" => opts.threshold = value().parse().unwrap_or_else(|_| usage()),
+            "--holdout-every" => opts.holdout_every = value().parse().unwrap_or_else(|_| usage()),
+            "--l2" => opts.l2 = value().parse().unwrap_or_else(|_| usage()),
+            "--model-version" => opts.model_version = value(),
+            "--model-out" => opts.model_out = Some(value()),
+            _ if arg.starts_with('-') || !opts.path.is_empty() => usage(),
+            _ => opts.path = arg,
+        }
+    }
+    if opts.path.is_empty() {
+        usage();
+    }
+    opts
+}
+
+fn load_examples(path: &str, config: &Config) -> Vec<Example> {
+    let contents = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("risk-calibrate: cannot read {}: {}", path, e);
+        process::exit(2);
+    });
+    let mut out = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let labeled: Labeled = serde_json::from_str(line).unwrap_or_else(|e| {
+            eprintln!("risk-calibrate: {}:{}: {}", path, i + 1, e);
+            process::exit(2);
+        });
+        let output = run_with_config(&labeled.input, config);
+        out.push(Example {
+            features: score_features(&labeled.input),
+            hits: output.flag_hits,
+            legacy_score: output.impact_score,
+            incident: labeled.incident,
+        });
+    }
+    out
+}
+
+/// Logistic design-matrix row: ln(1 + files), ln(1 + churn), then one 0/1
+/// per flag.
+fn row(example: &Example, flags: &[String]) -> Vec<f64> {
+    let mut row = vec![
+        (example.features.files as f64).ln_1p(),
+        (example.features.churn as f64).ln_1p(),
+    ];
+    row.extend(flag_columns(example, flags));
+    row
+}
+
+/// Legacy-shaped row: the formula's file and churn factors before their
+/// weights, then one 0/1 per flag.
+fn points_row(example: &Example, flags: &[String]) -> Vec<f64> {
+    let mut row = vec![
+        example.features.files.min(30) as f64,
+        (example.features.churn / 10).min(40) as f64,
+    ];
+    row.extend(flag_columns(example, flags));
+    row
+}
+
+fn flag_columns<'a>(example: &'a Example, flags: &'a [String]) -> impl Iterator<Item = f64> + 'a {
+    flags.iter().map(|f| {
+        if example.hits.iter().any(|h| h.flag.as_str() == f) {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+fn column_means(rows: &[Vec<f64>]) -> Vec<f64> {
+    let d = rows.first().map_or(0, Vec::len);
+    (0..d)
+        .map(|i| rows.iter().map(|r| r[i]).sum::<f64>() / rows.len() as f64)
+        .collect()
+}
+
+fn to_model(weights: &[f64], flags: &[String], version: &str) -> LogisticModel {
+    LogisticModel {
+        version: version.to_string(),
+        intercept: weights[0],
+        files: weights[1],
+        churn: weights[2],
+        flags: flags
+            .iter()
+            .cloned()
+            .zip(weights[3..].iter().copied())
+            .collect(),
+        default_flag: 0.0,
+    }
+}
+
+/// Score with the engine's own scorer, so the report matches production.
+fn fitted_score(scorer: &LogisticScorer, example: &Example) -> u8 {
+    let mut hits = example.hits.clone();
+    scorer.score(&example.features, &mut hits)
+}
+
+fn main() {
+    let opts = parse_args();
+    // The original formula is the baseline every fit is compared with.
+    let config = match Config::from_env() {
+        Ok(c) => Config {
+            scorer: Arc::new(LegacyScorer),
+            ..c
+        },
+        Err(e) => {
+            eprintln!("risk-calibrate: {}", e);
+            process::exit(2);
+        }
+    };
+    let examples = load_examples(&opts.path, &config);
+    if examples.is_empty() {
+        eprintln!("risk-calibrate: no examples in {}", opts.path);
+        process::exit(2);
+    }
+
+    let held_out =
+        |i: usize| opts.holdout_every > 0 && i % opts.holdout_every == opts.holdout_every - 1;
+    let mut train: Vec<&Example> = Vec::new();
+    let mut test: Vec<&Example> = Vec::new();
+    for (i, example) in examples.iter().enumerate() {
+        if held_out(i) {
+            test.push(example);
+        } else {
+            train.push(example);
+        }
+    }
+    if train.is_empty() {
+        eprintln!(
+            "risk-calibrate: --holdout-every {} leaves no training examples",
+            opts.holdout_every
+        );
+        process::exit(2);
+    }
+    if test.is_empty() {
+        test = train.clone();
+    }
+
+    let flags: Vec<String> = train
+        .iter()
+        .flat_map(|e| e.hits.iter().map(|h| h.flag.to_string()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let rows: Vec<Vec<f64>> = train.iter().map(|e| row(e, &flags)).collect();
+    let labels: Vec<bool> = train.iter().map(|e| e.incident).collect();
+    let weights = fit::fit_logistic(&rows, &labels, opts.l2);
+    let model = to_model(&weights, &flags, &opts.model_version);
+    let scorer = LogisticScorer::new(model.clone());
+    let points_rows: Vec<Vec<f64>> = train.iter().map(|e| points_row(e, &flags)).collect();
+    let points_fit = fit::fit_logistic(&points_rows, &labels, opts.l2);
+    let fitted_weights = Weights::fitted(&points_fit, &column_means(&points_rows), &flags);
+
+    let test_labels: Vec<bool> = test.iter().map(|e| e.incident).collect();
+    let legacy_scores: Vec<u8> = test.iter().map(|e| e.legacy_score).collect();
+    let reweighted_scores: Vec<u8> = test
+        .iter()
+        .map(|e| fitted_weights.score(e, &flags))
+        .collect();
+    let fitted_scores: Vec<u8> = test.iter().map(|e| fitted_score(&scorer, e)).collect();
+
+    let report = Report {
+        examples: examples.len(),
+        incidents: examples.iter().filter(|e| e.incident).count(),
+        train: train.len(),
+        evaluated: test.len(),
+        legacy: Metrics::compute(&legacy_scores, &test_labels, opts.threshold),
+        reweighted: Metrics::compute(&reweighted_scores, &test_labels, opts.threshold),
+        fitted: Metrics::compute(&fitted_scores, &test_labels, opts.threshold),
+        legacy_weights: Weights::legacy(),
+        fitted_weights,
+        model,
+    };
+
+    if let Some(out) = &opts.model_out {
+        let json = serde_json::to_string_pretty(&report.model).expect("model serializes");
+        fs::write(out, json + "\n").unwrap_or_else(|e| {
+            eprintln!("risk-calibrate: cannot write {}: {}", out, e);
+            process::exit(2);
+        });
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("report serializes")
+    );
+}
@@ -0,0 +1,102 @@
+//! Ranking and threshold metrics for scored examples.
+
+use serde::Serialize;
+
+/// How well a scorer separates incident pushes from the rest.
+#[derive(Debug, Clone, Serialize)]
+pub struct Metrics {
+    /// Area under the ROC curve; null when only one label is present.
+    pub auc: Option<f64>,
+    /// At `score >= threshold`; null when nothing is flagged.
+    pub precision: Option<f64>,
+    /// At `score >= threshold`; null when there are no incidents.
+    pub recall: Option<f64>,
+    pub threshold: u8,
+    /// Examples at or above the threshold.
+    pub flagged: usize,
+}
+
+impl Metrics {
+    pub fn compute(scores: &[u8], labels: &[bool], threshold: u8) -> Self {
+        let flagged = scores.iter().filter(|&&s| s >= threshold).count();
+        let positives = labels.iter().filter(|&&l| l).count();
+        let hits = scores
+            .iter()
+            .zip(labels)
+            .filter(|(&s, &l)| l && s >= threshold)
+            .count();
+        Self {
+            auc: auc(scores, labels),
+            precision: ratio(hits, flagged),
+            recall: ratio(hits, positives),
+            threshold,
+            flagged,
+        }
+    }
+}
+
+fn ratio(num: usize, den: usize) -> Option<f64> {
+    (den > 0).then(|| round(num as f64 / den as f64))
+}
+
+fn round(x: f64) -> f64 {
+    (x * 10_000.0).round() / 10_000.0
+}
+
+/// Probability that a random incident push outscores a random clean one
+/// (Mann–Whitney U; ties count half).
+pub fn auc(scores: &[u8], labels: &[bool]) -> Option<f64> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by_key(|&i| scores[i]);
+    // Average 1-based rank per tie group.
+    let mut ranks = vec![0.0; scores.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end < order.len() && scores[order[end]] == scores[order[start]] {
+            end += 1;
+        }
+        let avg = (start + 1 + end) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = avg;
+        }
+        start = end;
+    }
+    let pos = labels.iter().filter(|&&l| l).count() as f64;
+    let neg = labels.len() as f64 - pos;
+    if pos == 0.0 || neg == 0.0 {
+        return None;
+    }
+    let rank_sum: f64 = ranks
+        .iter()
+        .zip(labels)
+        .filter(|(_, &l)| l)
+        .map(|(r, _)| r)
+        .sum();
+    Some(round((rank_sum - pos * (pos + 1.0) / 2.0) / (pos * neg)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auc_perfect_inverse_and_ties() {
+        let labels = [false, false, true, true];
+        assert_eq!(auc(&[10, 20, 30, 40], &labels), Some(1.0));
+        assert_eq!(auc(&[40, 30, 20, 10], &labels), Some(0.0));
+        assert_eq!(auc(&[50, 50, 50, 50], &labels), Some(0.5));
+        assert_eq!(auc(&[1, 2], &[true, true]), None);
+    }
+
+    #[test]
+    fn precision_recall_at_threshold() {
+        let m = Metrics::compute(&[80, 60, 40, 20], &[true, false, true, false], 50);
+        assert_eq!(
+            (m.precision, m.recall, m.flagged),
+            (Some(0.5), Some(0.5), 2)
+        );
+        let none = Metrics::compute(&[10, 20], &[true, false], 50);
+        assert_eq!(none.precision, None);
+    }
+}